@@ -1,9 +1,10 @@
 // #[macro_use]
 use criterion::{
     criterion_group, criterion_main,
-    Criterion, Bencher, ParameterizedBenchmark
+    Criterion, Bencher, BenchmarkId
 };
 use rand::prelude::*;
+use std::convert::TryFrom;
 
 fn get_random_vec(n: usize) -> Vec<usize> {
     let mut rng: StdRng = StdRng::seed_from_u64(42);
@@ -22,10 +23,23 @@ macro_rules! create_bench_function {
 }
 
 macro_rules! create_bench {
-    ($p: expr, $f: ident, $($s: ident), *) => {
-        ParameterizedBenchmark::new(stringify!($f),
-        create_bench_function!($f), $p)
-    $(.with_function(stringify!($s), create_bench_function!($s)))*
+    ($g: expr, $p: expr, $($f: ident), *) => {
+        $(for n in $p.iter() {
+            $g.bench_with_input(BenchmarkId::new(stringify!($f), n), n,
+            create_bench_function!($f));
+        })*
+    }
+}
+
+macro_rules! create_bench_with {
+    ($g: expr, $p: expr, $f: ident, $($name: expr => $arg: expr), *) => {
+        $(for n in $p.iter() {
+            $g.bench_with_input(BenchmarkId::new($name, n), n,
+            |b: &mut Bencher, n: &usize| {
+                let s = get_random_vec(*n);
+                b.iter(|| sorting_rs::$f(&mut s.clone(), $arg));
+            });
+        })*
     }
 }
 
@@ -39,7 +53,9 @@ fn bench(c: &mut Criterion) {
         100_000_000, 250_000_000
     ];
 
-    let benchmark = create_bench! {
+    let mut group = c.benchmark_group("sort_bench");
+    create_bench! {
+        group,
         sizes,
         bingo_sort,
         bitonic_sort,
@@ -58,9 +74,12 @@ fn bench(c: &mut Criterion) {
         merge_bottom_up_sort,
         nheap_sort,
         oddeven_sort,
+        pairwise_sort,
         pancake_sort,
         quick_sort,
         quick_dual_sort,
+        quick_hoare_sort,
+        quick_3way_sort,
         selection_sort,
         selection_double_sort,
         shell_sort,
@@ -69,9 +88,107 @@ fn bench(c: &mut Criterion) {
         // slow_sort,
         // stooge_sort
     };
+    group.finish();
+}
+
+fn bench_shell_gaps(c: &mut Criterion) {
+    use sorting_rs::shell_sort::*;
+
+    let sizes: Vec<usize> = vec![
+        100, 1000, 10_000, 100_000, 1_000_000, 10_000_000
+    ];
 
-    c.bench("sort_bench", benchmark);
+    let mut group = c.benchmark_group("shell_gaps");
+    create_bench_with! {
+        group,
+        sizes,
+        shell_sort_with,
+        "shell" => &Shell,
+        "hibbard" => &Hibbard,
+        "knuth" => &Knuth,
+        "sedgewick" => &Sedgewick,
+        "pratt" => &Pratt,
+        "tokuda" => &Tokuda,
+        "ciura" => &Ciura
+    };
+    group.finish();
+}
+
+fn bench_comb_config(c: &mut Criterion) {
+    use sorting_rs::CombConfig;
+
+    let sizes: Vec<usize> = vec![
+        100, 1000, 10_000, 100_000, 1_000_000, 10_000_000
+    ];
+    let default = CombConfig::default();
+
+    let mut group = c.benchmark_group("comb_config");
+    create_bench_with! {
+        group,
+        sizes,
+        comb_sort_with,
+        "default" => &default,
+        "shrink_1.25" => &CombConfig {shrink: (5, 4), ..default},
+        "shrink_1.5" => &CombConfig {shrink: (3, 2), ..default},
+        "combsort11" => &CombConfig {combsort11: true, ..default},
+        "insertion_1" => &CombConfig {insertion_threshold: 1, ..default},
+        "insertion_4" => &CombConfig {insertion_threshold: 4, ..default},
+        "combsort11_insertion_4" => &CombConfig {
+            combsort11: true, insertion_threshold: 4, ..default
+        }
+    };
+    group.finish();
+}
+
+fn bench_heap_arity(c: &mut Criterion) {
+    let sizes: Vec<usize> = vec![
+        100, 1000, 10_000, 100_000, 1_000_000, 10_000_000
+    ];
+
+    let mut group = c.benchmark_group("heap_arity");
+    macro_rules! bench_arity {
+        ($($d: expr), *) => {
+            $(for n in sizes.iter() {
+                group.bench_with_input(BenchmarkId::new(
+                    concat!("dary_heap_sort_", $d), n), n,
+                |b: &mut Bencher, n: &usize| {
+                    let s = get_random_vec(*n);
+                    b.iter(|| sorting_rs::dary_heap_sort::<$d, _>(
+                        &mut s.clone()));
+                });
+            })*
+        }
+    }
+    bench_arity!(2, 3, 4, 5, 8, 16);
+    group.finish();
+}
+
+fn bench_network(c: &mut Criterion) {
+    let mut group = c.benchmark_group("network");
+    macro_rules! bench_network_size {
+        ($($n: expr), *) => {$(
+            // Arrays are many enough that branch predictor can't learn them
+            let arrays: Vec<[usize; $n]> = get_random_vec(100_000 * $n)
+                .chunks($n).map(|c| <[usize; $n]>::try_from(c).unwrap())
+                .collect();
+            group.bench_function(BenchmarkId::new("network_sort", $n),
+            |b: &mut Bencher| b.iter(|| {
+                let mut arrays = arrays.clone();
+                arrays.iter_mut().for_each(sorting_rs::network_sort);
+                arrays
+            }));
+            group.bench_function(BenchmarkId::new("insertion_sort", $n),
+            |b: &mut Bencher| b.iter(|| {
+                let mut arrays = arrays.clone();
+                arrays.iter_mut().for_each(|a| sorting_rs::insertion_sort(a));
+                arrays
+            }));
+        )*}
+    }
+    bench_network_size!(4, 8, 12, 16);
+    group.finish();
 }
 
-criterion_group!(benches, bench);
+criterion_group!(benches, bench, bench_shell_gaps, bench_comb_config,
+    bench_heap_arity, bench_network);
 criterion_main!(benches);
\ No newline at end of file
@@ -0,0 +1,344 @@
+//! Additional private binary to generate constant tables used by the sorting
+//! algorithms and print them as ready-to-paste Rust source.
+//! Tables can be generated for `u16`, `u32`, `u64`, `u128` and `usize`,
+//! generation stops at the last value fitting into the type.
+//! Check mode compares the tables compiled into the crate for the current
+//! target with the generated ones.
+//!
+//! Available tables: `leonardo`, `fibonacci`, `powers-of-two`, `ciura`,
+//! `tokuda`, `pratt`.
+//!
+//! # Usage:
+//! ```text
+//! cargo run --bin gen_tables -- leonardo --type u32
+//! cargo run --bin gen_tables -- pratt --type u16 --name PRATT_GAPS
+//! cargo run --bin gen_tables -- --check
+//! ```
+use std::env;
+use std::process;
+
+#[path = "../tables.rs"]
+mod tables;
+
+const USAGE: &str = "\
+Usage: gen_tables <TABLE> [--type <TYPE>] [--name <NAME>]
+       gen_tables --check
+
+TABLE: leonardo, fibonacci, powers-of-two, ciura, tokuda, pratt
+TYPE:  u16, u32, u64 (default), u128, usize";
+
+fn main() {
+    let mut table = None;
+    let mut ty = "u64".to_string();
+    let mut name = None;
+    let mut check = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {println!("{}", USAGE); return;},
+            "--check" => check = true,
+            "--type" => ty = args.next().unwrap_or_else(|| fail("--type")),
+            "--name" => name = Some(args.next()
+                .unwrap_or_else(|| fail("--name"))),
+            _ if table.is_none() && !arg.starts_with('-') => table = Some(arg),
+            _ => fail(&arg),
+        }
+    }
+
+    if check {
+        if !check_tables() {process::exit(1);}
+        return;
+    }
+
+    let table = table.unwrap_or_else(|| fail("missing table"));
+    let max = type_max(&ty).unwrap_or_else(|| fail(&ty));
+    let (values, default_name, title) = match table.as_str() {
+        "leonardo" => (recurrence(1, 1, 1, max), "LEO_NUMS",
+            "Leonardo numbers"),
+        "fibonacci" => (recurrence(0, 1, 0, max), "FIB_NUMS",
+            "Fibonacci numbers"),
+        "powers-of-two" => (powers_of_two(max), "POWERS_OF_TWO",
+            "Powers of 2"),
+        "ciura" => (ciura(max), "CIURA_GAPS",
+            "Ciura gaps, extended by multiplying by 2.25"),
+        "tokuda" => (tokuda(max), "TOKUDA_GAPS", "Tokuda gaps"),
+        "pratt" => (pratt(max), "PRATT_GAPS", "Pratt gaps"),
+        _ => fail(&table),
+    };
+    let name = name.as_deref().unwrap_or(default_name);
+    print!("{}", format_table(title, name, &ty, &values));
+}
+
+fn fail(arg: &str) -> ! {
+    eprintln!("Invalid argument: {}\n\n{}", arg, USAGE);
+    process::exit(2);
+}
+
+fn type_max(ty: &str) -> Option<u128> {
+    match ty {
+        "u16" => Some(u16::MAX as u128),
+        "u32" => Some(u32::MAX as u128),
+        "u64" => Some(u64::MAX as u128),
+        "u128" => Some(u128::MAX),
+        "usize" => Some(usize::MAX as u128),
+        _ => None,
+    }
+}
+
+/// Formats table as Rust constant, wrapping lines at 80 columns.
+fn format_table(title: &str, name: &str, ty: &str, values: &[u128])
+-> String {
+    let mut source = format!("/// {} fitting into `{}`\n", title, ty);
+    source += &format!("pub(crate) const {}: [{}; {}] = [\n", name, ty,
+        values.len());
+    let mut line = String::from("   ");
+    for value in values {
+        let item = format!(" {},", value);
+        if line.len() + item.len() > 80 {
+            source += &line;
+            source.push('\n');
+            line = String::from("   ");
+        }
+        line += &item;
+    }
+    if !values.is_empty() {
+        source += &line;
+        source.push('\n');
+    }
+    source += "];\n";
+    source
+}
+
+/// Sequence where every number is the sum of two previous ones and `add`.
+fn recurrence(mut n0: u128, mut n1: u128, add: u128, max: u128) -> Vec<u128> {
+    let mut table = Vec::new();
+    while n0 <= max {
+        table.push(n0);
+        match n0.checked_add(n1).and_then(|n| n.checked_add(add)) {
+            Some(n) => {
+                n0 = n1;
+                n1 = n;
+            },
+            None => {
+                if n1 <= max {table.push(n1);}
+                break;
+            },
+        }
+    }
+    table
+}
+
+fn powers_of_two(max: u128) -> Vec<u128> {
+    let mut table = Vec::new();
+    let mut power = Some(2u128);
+    while let Some(p) = power.filter(|p| *p <= max) {
+        table.push(p);
+        power = p.checked_mul(2);
+    }
+    table
+}
+
+fn ciura(max: u128) -> Vec<u128> {
+    let mut table: Vec<u128> = [1, 4, 10, 23, 57, 132, 301, 701, 1750].iter()
+        .copied().filter(|gap| *gap <= max).collect();
+    if table.len() < 9 {return table;}
+
+    // Next gap is the previous one multiplied by 2.25 and rounded down
+    let mut gap = Some(1750u128);
+    while let Some(g) = gap {
+        gap = (g / 4).checked_mul(9).and_then(|n| n.checked_add(g % 4 * 9 / 4))
+            .filter(|n| *n <= max);
+        if let Some(n) = gap {table.push(n);}
+    }
+    table
+}
+
+/// Gaps are `ceil((9^k - 4^k) / (5 * 4^(k - 1)))`, numerator grows faster
+/// than the gaps, so it's calculated with big numbers.
+fn tokuda(max: u128) -> Vec<u128> {
+    let mut table = Vec::new();
+    let mut nine = Big(vec![1]);
+    let mut four = Big(vec![1]);
+
+    for k in 1.. {
+        nine.mul_small(9);
+        four.mul_small(4);
+        let mut gap = nine.clone();
+        gap.sub(&four);
+        let remainder = gap.div_small(5);
+        let lost = gap.shr(2 * (k - 1));
+        let gap = gap.to_u128()
+            .and_then(|g| g.checked_add((remainder != 0 || lost) as u128));
+        match gap {
+            Some(g) if g <= max => table.push(g),
+            _ => break,
+        }
+    }
+    table
+}
+
+/// Numbers of form 2<sup>p</sup>3<sup>q</sup> in ascending order.
+fn pratt(max: u128) -> Vec<u128> {
+    let mut table = Vec::new();
+    let mut power2 = Some(1u128);
+    while let Some(p2) = power2.filter(|p| *p <= max) {
+        let mut gap = Some(p2);
+        while let Some(g) = gap.filter(|g| *g <= max) {
+            table.push(g);
+            gap = g.checked_mul(3);
+        }
+        power2 = p2.checked_mul(2);
+    }
+    table.sort_unstable();
+    table
+}
+
+/// Big unsigned number, little-endian digits in base 2<sup>32</sup>.
+#[derive(Clone)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn mul_small(&mut self, m: u32) {
+        let mut carry = 0u64;
+        for digit in self.0.iter_mut() {
+            let n = *digit as u64 * m as u64 + carry;
+            *digit = n as u32;
+            carry = n >> 32;
+        }
+        if carry > 0 {self.0.push(carry as u32);}
+    }
+
+    /// Subtracts not greater number.
+    fn sub(&mut self, other: &Big) {
+        let mut borrow = 0i64;
+        for (i, digit) in self.0.iter_mut().enumerate() {
+            let n = *digit as i64 - other.0.get(i).copied().unwrap_or(0) as i64
+                - borrow;
+            borrow = (n < 0) as i64;
+            *digit = (n + (borrow << 32)) as u32;
+        }
+        self.trim();
+    }
+
+    /// Divides by `d` in place and returns remainder.
+    fn div_small(&mut self, d: u32) -> u32 {
+        let mut remainder = 0u64;
+        for digit in self.0.iter_mut().rev() {
+            let n = (remainder << 32) | *digit as u64;
+            *digit = (n / d as u64) as u32;
+            remainder = n % d as u64;
+        }
+        self.trim();
+        remainder as u32
+    }
+
+    /// Shifts right by `s` bits, returns `true` if any set bit was dropped.
+    fn shr(&mut self, s: usize) -> bool {
+        let (words, bits) = (s / 32, s % 32);
+        let mut lost = self.0.iter().take(words).any(|d| *d != 0);
+        if words >= self.0.len() {
+            self.0.clear();
+            return lost;
+        }
+        self.0.drain(..words);
+        if bits > 0 {
+            lost |= self.0[0] & ((1 << bits) - 1) != 0;
+            for i in 0..self.0.len() {
+                let high = self.0.get(i + 1).copied().unwrap_or(0);
+                self.0[i] = (self.0[i] >> bits) | (high << (32 - bits));
+            }
+        }
+        self.trim();
+        lost
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        if self.0.len() > 4 {return None;}
+        Some(self.0.iter().rev().fold(0, |n, d| (n << 32) | *d as u128))
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {self.0.pop();}
+    }
+}
+
+/// Compares tables compiled into the crate with generated ones, returns
+/// `true` if all of them match.
+fn check_tables() -> bool {
+    let usize_max = usize::MAX as u128;
+    let u64_max = u64::MAX as u128;
+    let checks: [(&str, Vec<u128>, Vec<u128>); 7] = [
+        ("POWERS_OF_TWO", widen(&tables::POWERS_OF_TWO),
+            powers_of_two(usize_max)),
+        ("LEO_NUMS", widen(&tables::LEO_NUMS), recurrence(1, 1, 1, usize_max)),
+        ("CIURA_GAPS", widen(&tables::CIURA_GAPS), ciura(usize_max)),
+        ("CIURA_GAPS_64", tables::CIURA_GAPS_64.iter().map(|n| *n as u128)
+            .collect(), ciura(u64_max)),
+        ("TOKUDA_GAPS", widen(&tables::TOKUDA_GAPS), tokuda(usize_max)),
+        ("TOKUDA_GAPS_64", tables::TOKUDA_GAPS_64.iter().map(|n| *n as u128)
+            .collect(), tokuda(u64_max)),
+        ("PRATT_GAPS", widen(&tables::PRATT_GAPS), pratt(usize_max)),
+    ];
+
+    let mut ok = true;
+    for (name, compiled, generated) in checks.iter() {
+        match compiled.iter().zip(generated.iter()).position(|(a, b)| a != b) {
+            Some(i) => {
+                println!("{}: differs at index {}: {} != {}", name, i,
+                    compiled[i], generated[i]);
+                ok = false;
+            },
+            None if compiled.len() != generated.len() => {
+                println!("{}: has {} entries instead of {}", name,
+                    compiled.len(), generated.len());
+                ok = false;
+            },
+            None => println!("{}: ok, {} entries", name, compiled.len()),
+        }
+    }
+    ok
+}
+
+fn widen(table: &[usize]) -> Vec<u128> {
+    table.iter().map(|n| *n as u128).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_tables() {
+        debug_assert!(check_tables());
+    }
+    #[test]
+    fn test_sequences() {
+        debug_assert_eq!(recurrence(0, 1, 0, 13),
+            vec![0, 1, 1, 2, 3, 5, 8, 13]);
+        debug_assert_eq!(powers_of_two(100), vec![2, 4, 8, 16, 32, 64]);
+        debug_assert_eq!(ciura(20), vec![1, 4, 10]);
+        debug_assert_eq!(tokuda(250), vec![1, 4, 9, 20, 46, 103, 233]);
+        debug_assert_eq!(pratt(10), vec![1, 2, 3, 4, 6, 8, 9]);
+    }
+    #[test]
+    fn test_overflow() {
+        let leonardo = recurrence(1, 1, 1, u128::MAX);
+        let last = leonardo.len() - 1;
+        debug_assert!(leonardo[last].checked_add(leonardo[last - 1] + 1)
+            .is_none());
+        debug_assert_eq!(powers_of_two(u128::MAX).len(), 127);
+        debug_assert_eq!(pratt(u16::MAX as u128).last(), Some(&62208));
+        debug_assert!(tokuda(u128::MAX).windows(2).all(|w| w[0] < w[1]));
+        debug_assert!(ciura(u128::MAX).windows(2).all(|w| w[0] < w[1]));
+    }
+    #[test]
+    fn test_format_table() {
+        let source = format_table("Powers of 2", "POWERS", "u16",
+            &powers_of_two(u16::MAX as u128));
+        debug_assert!(source.starts_with("/// Powers of 2 fitting into `u16`\n\
+            pub(crate) const POWERS: [u16; 15] = [\n    2, 4, 8,"));
+        debug_assert!(source.ends_with(" 32768,\n];\n"));
+        debug_assert!(source.lines().all(|line| line.len() <= 80));
+    }
+}
@@ -0,0 +1,410 @@
+//! Additional private binary to search for small or shallow sorting networks
+//! and print them as ready-to-paste entries of `networks!` in
+//! `network_sort.rs`.
+//!
+//! Default search is evolutionary: a network is grown by greedily adding the
+//! comparator which leaves the fewest distinct outputs for all 0-1 inputs,
+//! ties are broken randomly. Then the best network is mutated by cutting it
+//! at a random point and growing the rest again, redundant comparators are
+//! removed, and the result is kept if it isn't worse. `--depth` grows the
+//! network layer by layer and compares depth before size.
+//!
+//! Exhaustive search tries all comparator sequences with iterative deepening
+//! on size. Prefixes which lead to an already visited set of outputs with
+//! no less comparators left are pruned, as well as orderings of independent
+//! comparators other than ascending. It finds optimal networks, but is only
+//! practical for up to 7 inputs, which already take about a minute.
+//!
+//! Every network is verified with 0-1 principle before it's printed. `--all`
+//! prints the whole `networks!` body, keeping current networks of
+//! `network_sort` where the search didn't find better ones.
+//!
+//! # Usage:
+//! ```text
+//! cargo run --release --bin search_networks -- 10 --iterations 5000
+//! cargo run --release --bin search_networks -- 8 --depth --seed 7
+//! cargo run --release --bin search_networks -- 5 --exhaustive
+//! cargo run --release --bin search_networks -- --all
+//! ```
+use sorting_rs::network::Network;
+use std::collections::HashMap;
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+Usage: search_networks <N> [--depth] [--iterations <K>] [--seed <S>]
+       search_networks <N> --exhaustive
+       search_networks --all [--depth] [--iterations <K>] [--seed <S>]
+
+N: number of inputs, 2 to 16";
+
+/// Largest number of inputs, the same as the limit of `network_sort`.
+const MAX_INPUTS: usize = 16;
+
+fn main() {
+    let mut inputs = None;
+    let mut all = false;
+    let mut shallow = false;
+    let mut exhaustive = false;
+    let mut iterations = 1000;
+    let mut seed = 1;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {println!("{}", USAGE); return;},
+            "--all" => all = true,
+            "--depth" => shallow = true,
+            "--exhaustive" => exhaustive = true,
+            "--iterations" => iterations = number(args.next(), "--iterations"),
+            "--seed" => seed = number(args.next(), "--seed") as u64,
+            _ if inputs.is_none() && !arg.starts_with('-') => {
+                inputs = Some(number(Some(arg), "N"));
+            },
+            _ => fail(&arg),
+        }
+    }
+
+    let sizes = match inputs {
+        Some(n) if !all && (2..=MAX_INPUTS).contains(&n) => n..=n,
+        None if all && !exhaustive => 2..=MAX_INPUTS,
+        _ => fail("N"),
+    };
+
+    let mut search = Search::new(seed);
+    for n in sizes {
+        let found = if exhaustive {
+            exhaustive_search(n)
+        } else {
+            search.evolve(n, shallow, iterations)
+        };
+        let found = Network::from_comparators(n, &found);
+        assert!(found.is_sorting(), "Network for {} inputs doesn't sort", n);
+
+        let current = Network::optimal(n).unwrap();
+        eprintln!("{} inputs: found {} comparators, depth {}; current {}, \
+            depth {}", n, found.size(), found.depth(), current.size(),
+            current.depth());
+        let network = if all && score(&current, shallow) <= score(&found,
+            shallow) {current} else {found};
+        print!("{}", format_network(&network));
+    }
+}
+
+fn number(arg: Option<String>, name: &str) -> usize {
+    arg.and_then(|a| a.parse().ok()).unwrap_or_else(|| fail(name))
+}
+
+fn fail(arg: &str) -> ! {
+    eprintln!("Invalid argument: {}\n\n{}", arg, USAGE);
+    process::exit(2);
+}
+
+/// Networks are compared by size and then depth, or the other way round.
+fn score(network: &Network, shallow: bool) -> (usize, usize) {
+    if shallow {(network.depth(), network.size())}
+    else {(network.size(), network.depth())}
+}
+
+/// Formats network as an entry of `networks!`, wrapping lines at 80 columns.
+fn format_network(network: &Network) -> String {
+    let mut source = format!("    // {} inputs, {} comparator{}, depth {}\n",
+        network.inputs(), network.size(),
+        if network.size() == 1 {""} else {"s"}, network.depth());
+    let mut line = format!("    {} => [", network.inputs());
+    let mut first = true;
+    for (i, j) in network.comparators() {
+        let item = format!("({}, {})", i, j);
+        if first {
+            first = false;
+        } else if line.len() + item.len() + 3 > 80 {
+            source += &line;
+            source += ",\n";
+            line = String::from("        ");
+        } else {
+            line += ", ";
+        }
+        line += &item;
+    }
+    source += &line;
+    source += "],\n";
+    source
+}
+
+/// Set of distinct outputs of a network for all 0-1 inputs.
+#[derive(Clone)]
+struct Outputs {
+    vectors: Vec<u32>,
+    present: Vec<bool>,
+}
+
+impl Outputs {
+    fn new(n: usize) -> Self {
+        Self {
+            vectors: (0..1 << n).collect(),
+            present: vec![true; 1 << n],
+        }
+    }
+
+    /// Number of outputs which would be left after comparator `(i, j)`,
+    /// vectors which get swapped merge with the ones already present.
+    fn count_after(&self, i: usize, j: usize) -> usize {
+        let mask = (1 << i) | (1 << j);
+        let merged = self.vectors.iter()
+            .filter(|v| *v & mask == 1 << i)
+            .filter(|v| self.present[(*v ^ mask) as usize])
+            .count();
+        self.vectors.len() - merged
+    }
+
+    /// Returns `true` if the comparator changed any output.
+    fn apply(&mut self, i: usize, j: usize) -> bool {
+        let mask = (1 << i) | (1 << j);
+        let mut changed = false;
+        for v in self.vectors.iter_mut() {
+            if *v & mask == 1 << i {
+                self.present[*v as usize] = false;
+                *v ^= mask;
+                changed = true;
+            }
+        }
+        for v in self.vectors.iter() {
+            self.present[*v as usize] = true;
+        }
+
+        let present = &mut self.present;
+        self.vectors.retain(|v| {
+            let first = present[*v as usize];
+            present[*v as usize] = false;
+            first
+        });
+        for v in self.vectors.iter() {
+            present[*v as usize] = true;
+        }
+        changed
+    }
+
+    /// Only the `n + 1` sorted vectors are left when the network sorts.
+    fn is_sorted(&self, n: usize) -> bool {
+        self.vectors.len() == n + 1
+    }
+}
+
+struct Search {
+    state: u64,
+}
+
+impl Search {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator.
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_random() as u128 * n as u128) >> 64) as usize
+    }
+
+    fn evolve(&mut self, n: usize, shallow: bool, iterations: usize)
+    -> Vec<(usize, usize)> {
+        let mut best = self.grow(n, &[], shallow);
+        let mut best_score = score(&Network::from_comparators(n, &best),
+            shallow);
+
+        for _ in 0..iterations {
+            // Shallow networks are cut at layer boundaries, so the grown
+            // part starts with a new layer
+            let network = Network::from_comparators(n, &best);
+            let prefix: Vec<(usize, usize)> = if shallow {
+                let cut = self.below(network.depth());
+                network.layers()[..cut].iter().flatten().copied().collect()
+            } else {
+                best[..self.below(best.len())].to_vec()
+            };
+
+            let candidate = prune(n, self.grow(n, &prefix, shallow));
+            let candidate_score = score(&Network::from_comparators(n,
+                &candidate), shallow);
+            if candidate_score <= best_score {
+                best = candidate;
+                best_score = candidate_score;
+            }
+        }
+        best
+    }
+
+    /// Extends prefix greedily until the network sorts.
+    fn grow(&mut self, n: usize, prefix: &[(usize, usize)], shallow: bool)
+    -> Vec<(usize, usize)> {
+        let mut outputs = Outputs::new(n);
+        let mut network = prefix.to_vec();
+        for &(i, j) in prefix {
+            outputs.apply(i, j);
+        }
+
+        let mut used = vec![false; n];
+        while !outputs.is_sorted(n) {
+            let mut best = Vec::new();
+            let mut best_count = outputs.vectors.len();
+            for i in 0..n {
+                for j in i + 1..n {
+                    if shallow && (used[i] || used[j]) {continue;}
+                    let count = outputs.count_after(i, j);
+                    if count < best_count {
+                        best.clear();
+                        best_count = count;
+                    }
+                    if count == best_count && count < outputs.vectors.len() {
+                        best.push((i, j));
+                    }
+                }
+            }
+
+            // Comparators merging no outputs can still be needed, the one
+            // changing them is taken then
+            if best.is_empty() {
+                if shallow && used.iter().any(|u| *u) {
+                    used.iter_mut().for_each(|u| *u = false);
+                    continue;
+                }
+                for i in 0..n {
+                    for j in i + 1..n {
+                        if !(shallow && (used[i] || used[j]))
+                            && outputs.clone().apply(i, j) {
+                            best.push((i, j));
+                        }
+                    }
+                }
+            }
+
+            let (i, j) = best[self.below(best.len())];
+            outputs.apply(i, j);
+            network.push((i, j));
+            used[i] = true;
+            used[j] = true;
+        }
+        network
+    }
+}
+
+/// Removes comparators which the network sorts without.
+fn prune(n: usize, mut network: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut k = network.len();
+    while k > 0 {
+        k -= 1;
+        let comparator = network.remove(k);
+        if !Network::from_comparators(n, &network).is_sorting() {
+            network.insert(k, comparator);
+        }
+    }
+    network
+}
+
+fn exhaustive_search(n: usize) -> Vec<(usize, usize)> {
+    let mut network = Vec::new();
+    for size in 0.. {
+        let mut visited = HashMap::new();
+        if extend(n, &Outputs::new(n), size, &mut network, &mut visited) {
+            break;
+        }
+    }
+    network
+}
+
+/// Depth-first search for a sorting extension of `network` with at most
+/// `left` comparators.
+fn extend(n: usize, outputs: &Outputs, left: usize,
+network: &mut Vec<(usize, usize)>, visited: &mut HashMap<Vec<u32>, usize>)
+-> bool {
+    if outputs.is_sorted(n) {return true;}
+    if left == 0 {return false;}
+
+    let mut key = outputs.vectors.clone();
+    key.sort_unstable();
+    match visited.get(&key) {
+        Some(&tried) if tried >= left => return false,
+        _ => {visited.insert(key, left);},
+    }
+
+    // The first comparator can be any by symmetry
+    let pairs: Vec<(usize, usize)> = if network.is_empty() {vec![(0, 1)]}
+        else {(0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).collect()};
+    for (i, j) in pairs {
+        if let Some(&(a, b)) = network.last() {
+            let independent = a != i && a != j && b != i && b != j;
+            if independent && (i, j) < (a, b) {continue;}
+        }
+
+        let mut next = outputs.clone();
+        if !next.apply(i, j) {continue;}
+        network.push((i, j));
+        if extend(n, &next, left - 1, network, visited) {return true;}
+        network.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outputs() {
+        let mut outputs = Outputs::new(3);
+        debug_assert_eq!(outputs.count_after(0, 1), 6);
+        debug_assert!(outputs.apply(0, 1));
+        debug_assert_eq!(outputs.vectors.len(), 6);
+        debug_assert!(!outputs.apply(0, 1));
+        outputs.apply(1, 2);
+        outputs.apply(0, 1);
+        debug_assert!(outputs.is_sorted(3));
+    }
+    #[test]
+    fn test_evolve() {
+        let mut search = Search::new(1);
+        for n in 2..=8 {
+            for shallow in [false, true].iter() {
+                let found = search.evolve(n, *shallow, 20);
+                debug_assert!(Network::from_comparators(n, &found)
+                    .is_sorting());
+            }
+        }
+        let found = search.evolve(6, false, 100);
+        debug_assert_eq!(found.len(), 12);
+    }
+    #[test]
+    fn test_exhaustive() {
+        let sizes: Vec<usize> = (2..=5).map(|n| exhaustive_search(n).len())
+            .collect();
+        debug_assert_eq!(sizes, vec![1, 3, 5, 9]);
+    }
+    #[test]
+    fn test_prune() {
+        let network = prune(3, vec![(0, 1), (0, 1), (1, 2), (0, 2), (0, 1)]);
+        debug_assert!(Network::from_comparators(3, &network).is_sorting());
+        debug_assert_eq!(network.len(), 3);
+    }
+    #[test]
+    fn test_format_network() {
+        let source = format_network(&Network::optimal(4).unwrap());
+        debug_assert_eq!(source, "    // 4 inputs, 5 comparators, depth 3\n    \
+            4 => [(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],\n");
+        debug_assert!(format_network(&Network::optimal(2).unwrap())
+            .starts_with("    // 2 inputs, 1 comparator, depth 1\n"));
+    }
+    #[test]
+    fn test_format_current() {
+        let source = include_str!("../network_sort.rs");
+        for n in 2..=MAX_INPUTS {
+            let entry = format_network(&Network::optimal(n).unwrap());
+            debug_assert!(source.contains(&entry), "{}", entry);
+        }
+    }
+}
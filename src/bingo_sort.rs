@@ -17,7 +17,6 @@
 /// sorting_rs::bingo_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn bingo_sort<T: PartialOrd + Copy>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -45,9 +44,9 @@ pub fn bingo_sort<T: PartialOrd + Copy>(input: &mut [T]) {
 fn get_min_max<T: PartialOrd + Copy>(input: &[T]) -> [T; 2] {
     let mut min = input[0];
     let mut max = input[0];
-    for i in 1..input.len() {
-        if input[i] < min {min = input[i];}
-        if input[i] > max {max = input[i];}
+    for &item in &input[1..] {
+        if item < min {min = item;}
+        if item > max {max = item;}
     }
     [min, max]
 }
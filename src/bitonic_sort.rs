@@ -1,3 +1,6 @@
+use crate::error::{check_sorted, SortError};
+use crate::network::{layer_comparators, Layer};
+
 /// Sorts a slice in-place using
 /// [Bitonic sort](https://en.wikipedia.org/wiki/Bitonic_sorter).
 /// All kinds of slices can be sorted as long as they implement
@@ -8,18 +11,23 @@
 /// 
 /// Default implementations of this algorithm demand power of two elements in
 /// array, but for API consistency any length is supported in case of this
-/// implementation. This flexibility comes at cost though: the least effective
-/// operation is `Vec::drain(..index)`, which removes additional values from the
-/// beginning of Vec. Also algotithm has to place new <T> instances to make
-/// array compatible with logic.
+/// implementation. The network for the next power of two is run, and
+/// comparators touching positions past the end are skipped, which works as
+/// if the array was padded with elements greater than all others, so no
+/// elements have to be added.
 /// 
-/// In the current implementation maximum supported array length is
-/// `9223372036854775808`. Next power of two which is `usize::MAX` is actually
-/// 2<sup>64</sup>-1, but isn't supported in most of known cases anyway, as it
-/// occupies 147.6 exabytes of memory.
+/// In the current implementation maximum supported array length is the
+/// largest power of two fitting into `usize`, which is `9223372036854775808`
+/// on 64-bit systems and `2147483648` on 32-bit ones. Next power of two would
+/// not fit into `usize` anyway. `bitonic_sort` panics on longer arrays, and
+/// `try_bitonic_sort` returns an error, as well as when comparisons turn out
+/// to be inconsistent.
 /// 
-/// Performance-wise all the available 64 bit powers of two are calculated and
-/// placed into const.
+/// `par_bitonic_sort` runs the network on scoped threads, one per available
+/// core, `par_bitonic_sort_with` takes the number of threads. Every thread
+/// executes comparators of its part of the slice and waits for the others
+/// before the next layer. It sorts slices of any length without padding:
+/// comparators touching positions above the length are skipped.
 ///
 /// # Examples
 /// ```rust
@@ -32,60 +40,61 @@
 /// sorting_rs::bitonic_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
+/// ```rust
+/// let mut vec: Vec<u32> = (0..1000).rev().collect();
+/// sorting_rs::par_bitonic_sort_with(&mut vec, 4);
+/// assert_eq!(vec, (0..1000).collect::<Vec<u32>>());
+/// ```
+pub fn bitonic_sort<T: PartialOrd>(input: &mut [T]) {
+    if input.len() > MAX_LEN {panic!("Array is too big")}
+    bit_sort(input);
+}
 
-pub fn bitonic_sort<T: PartialOrd + Default + Clone>(input: &mut Vec<T>) {
-    if input.len() < 2 {return;}
-    else if input.len() > 9223372036854775808 {panic!("Array is too big")}
-    
-    let in_len = input.len();
-
-    // Check if input array has length of power of 2 and add None to fill it up
-    let mut add_len = 0;
-    println!("{}", add_len);
-
-    for (i, num) in crate::POWERS_OF_TWO.iter().enumerate() {
-        if in_len == *num {add_len = 0;}
-        if i > 0 {
-            if in_len < *num {if in_len > crate::POWERS_OF_TWO[i - 1] {
-                add_len = num - in_len;}
-            }
-        }
-    }
-
-    if add_len > 0 {
-        input.append(&mut vec![T::default(); add_len]);
+pub fn try_bitonic_sort<T: PartialOrd>(input: &mut [T])
+-> Result<(), SortError> {
+    if input.len() > MAX_LEN {
+        return Err(SortError::TooLarge {len: input.len(), max: MAX_LEN});
     }
-    
-    bit_sort(input, true);
-    input.drain(..add_len);
+    bit_sort(input);
+    check_sorted(input)
 }
 
-
-fn bit_sort<T: PartialOrd>(input: &mut [T], mode: bool) {
-    if input.len() > 1 {
-        let mid_point = input.len() / 2;
-        bit_sort(&mut input[..mid_point], true);
-        bit_sort(&mut input[mid_point..], false);
-        sub_sort(input, mode);
-    }
+pub fn par_bitonic_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_bitonic_sort_with(input, crate::network::default_threads());
 }
-fn sub_sort<T: PartialOrd>(input: &mut [T], mode: bool) {
-    if input.len() > 1 {
-        compare_and_swap(input, mode);
-        let mid_point = input.len() / 2;
-        sub_sort(&mut input[..mid_point], mode);
-        sub_sort(&mut input[mid_point..], mode);
-    }
+
+pub fn par_bitonic_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    let layers = crate::network::bitonic_layers(input.len());
+    crate::network::par_apply_layers(input, &layers, threads, false);
 }
-fn compare_and_swap<T: PartialOrd>(input: &mut [T], mode: bool) {
-    let mid_point = input.len() / 2;
-    for i in 0..mid_point {
-        if (input[i] > input[mid_point + i]) == mode {
-            input.swap(i, mid_point + i);
+
+/// Largest supported length.
+const MAX_LEN: usize = crate::POWERS_OF_TWO[crate::POWERS_OF_TWO.len() - 1];
+
+/// Runs layers of the bitonic network one by one, so the stack depth is
+/// constant.
+fn bit_sort<T: PartialOrd>(input: &mut [T]) {
+    let n = input.len();
+    if n < 2 {return;}
+
+    for level in 1..=n.next_power_of_two().trailing_zeros() {
+        let k = 1 << level;
+        apply_layer(input, Layer::Mirror(k));
+        let mut j = k / 4;
+        while j > 0 {
+            apply_layer(input, Layer::Xor(j));
+            j /= 2;
         }
     }
 }
 
+fn apply_layer<T: PartialOrd>(input: &mut [T], layer: Layer) {
+    layer_comparators(input.len(), &[layer], |i, j| {
+        if input[i] > input[j] {input.swap(i, j);}
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,12 +124,60 @@ mod tests {
         debug_assert_eq!(vector_in, vec!['c', 'r', 's', 't', 'u']);
     }
     #[test]
+    fn test_bitonic_negative() {
+        let mut vector_in = vec![-1, -2, -3];
+        bitonic_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![-3, -2, -1]);
+    }
+    #[test]
+    fn test_bitonic_lengths() {
+        for len in 0..70 {
+            let input: Vec<i32> = (0..len).map(|x| (x * 367) % 101 - 50)
+                .collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let mut vector_in = input.clone();
+            bitonic_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+            let mut vector_in = input;
+            debug_assert_eq!(try_bitonic_sort(&mut vector_in), Ok(()));
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
+    fn test_try_bitonic_errors() {
+        // Zero-sized elements take no memory, so any length can be made
+        let mut vector_in = vec![(); usize::MAX];
+        debug_assert_eq!(try_bitonic_sort(&mut vector_in),
+            Err(SortError::TooLarge {len: usize::MAX, max: MAX_LEN}));
+
+        let mut vector_in = vec![2.0, f64::NAN, 1.0];
+        debug_assert!(matches!(try_bitonic_sort(&mut vector_in),
+            Err(SortError::InconsistentOrder {..})));
+    }
+    #[test]
     fn test_bitonic_empty() {
         let mut vector_in:Vec<u8> = vec![];
         bitonic_sort(&mut vector_in);
         debug_assert_eq!(vector_in, vec![]);
     }
     #[test]
+    fn test_par_bitonic() {
+        for len in [0, 1, 2, 5, 17, 100, 1000, 4096].iter() {
+            let input: Vec<i32> = (0..*len).map(|x| (x * 367) % 101).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            for threads in [1, 3, 8].iter() {
+                let mut vector_in = input.clone();
+                par_bitonic_sort_with(&mut vector_in, *threads);
+                debug_assert_eq!(vector_in, expected);
+            }
+            let mut vector_in = input.clone();
+            par_bitonic_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
     fn test_bitonic_len1() {
         let mut vector_in = vec![1];
         bitonic_sort(&mut vector_in);
@@ -14,7 +14,6 @@
 /// sorting_rs::bubble_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn bubble_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
     
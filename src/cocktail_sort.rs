@@ -21,7 +21,6 @@
 /// sorting_rs::cocktail_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn cocktail_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -10,6 +10,14 @@
 /// the gap size shrinks until it is finally 1, where it is the same as bubble
 /// sort.
 ///
+/// `comb_sort_with` takes [`CombConfig`], which controls the shrink factor,
+/// the Combsort11 rule and the switch to insertion sort. Shrink factor is
+/// given as a fraction, so gaps are calculated using only integer arithmetic.
+/// Combsort11 rule replaces gaps 9 and 10 with 11, which avoids slow final
+/// passes for some inputs. Insertion sort is used instead of comb passes when
+/// the gap reaches `insertion_threshold`, as by then the input is nearly
+/// sorted and insertion sort needs only one pass. Threshold `0` disables it.
+///
 /// # Examples
 /// ```rust
 /// let mut vec = vec![9, 7, 8, 5, 1];
@@ -21,23 +29,46 @@
 /// sorting_rs::comb_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// use sorting_rs::CombConfig;
+///
+/// let config = CombConfig {
+///     shrink: (5, 4),
+///     combsort11: true,
+///     insertion_threshold: 4,
+/// };
+/// let mut vec = vec![9, 7, 8, 5, 1];
+/// sorting_rs::comb_sort_with(&mut vec, &config);
+/// assert_eq!(vec, &[1, 5, 7, 8, 9]);
+/// ```
 pub fn comb_sort<T: PartialOrd>(input: &mut [T]) {
+    comb_sort_with(input, &CombConfig::default());
+}
+
+pub fn comb_sort_with<T: PartialOrd>(input: &mut [T], config: &CombConfig) {
     if input.len() < 2 {return;}
 
-    let len = input.len();
-    let inv_shrink: f32 = 1.0 / 1.3;
+    let (num, den) = config.shrink;
+    assert!(den > 0 && num > den, "Shrink factor must be greater than 1");
 
+    let len = input.len();
     let mut gap = len;
-    let mut sorted = len < 2;
+    let mut sorted = false;
 
     while !sorted {
-        gap = (gap as f32 * inv_shrink).floor() as usize;
+        gap = (gap as u128 * den as u128 / num as u128) as usize;
+        if config.combsort11 && (gap == 9 || gap == 10) {
+            gap = 11;
+        }
 
         if gap <= 1 {
             gap = 1;
             sorted = true;
         }
+        if config.insertion_threshold > 0 && gap <= config.insertion_threshold {
+            crate::insertion_sort(input);
+            return;
+        }
 
         for i in 0..len - gap {
             if input[i] > input[i + gap] {
@@ -48,6 +79,28 @@ pub fn comb_sort<T: PartialOrd>(input: &mut [T]) {
     }
 }
 
+/// Parameters of [`comb_sort_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombConfig {
+    /// Shrink factor as `(numerator, denominator)`, must be greater than 1.
+    pub shrink: (usize, usize),
+    /// Replaces gaps 9 and 10 with 11.
+    pub combsort11: bool,
+    /// Gap at which the rest is sorted by insertion sort, `0` disables it.
+    pub insertion_threshold: usize,
+}
+
+impl Default for CombConfig {
+    /// Shrink factor 1.3 without Combsort11 rule and insertion sort.
+    fn default() -> Self {
+        Self {
+            shrink: (13, 10),
+            combsort11: false,
+            insertion_threshold: 0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,6 +112,43 @@ mod tests {
         debug_assert_eq!(vector_in, vec![10, 11, 11, 12, 20, 24, 30, 44]);
     }
     #[test]
+    fn test_comb_with() {
+        let configs = [
+            CombConfig::default(),
+            CombConfig {shrink: (5, 4), ..CombConfig::default()},
+            CombConfig {shrink: (2, 1), ..CombConfig::default()},
+            CombConfig {combsort11: true, ..CombConfig::default()},
+            CombConfig {insertion_threshold: 1, ..CombConfig::default()},
+            CombConfig {
+                shrink: (13, 10), combsort11: true, insertion_threshold: 8
+            },
+        ];
+        for config in configs.iter() {
+            let mut vector_in: Vec<i32> = (0..1000)
+                .map(|x| (x * 367) % 1000).collect();
+            comb_sort_with(&mut vector_in, config);
+            debug_assert_eq!(vector_in, (0..1000).collect::<Vec<i32>>());
+        }
+    }
+    #[test]
+    fn test_comb_without_insertion() {
+        let count = std::cell::Cell::new(0);
+        let mut vector_in: Vec<Counted> = [2, 0, 3, 4, 5, 6, 1].iter()
+            .map(|&x| Counted(x, &count)).collect();
+        comb_sort(&mut vector_in);
+        debug_assert!(vector_in.windows(2).all(|w| w[0].0 <= w[1].0));
+        // Gaps 5, 3 and 2 take 11 comparisons, then three passes with gap 1
+        // take 18, insertion sort would finish after the first of them
+        debug_assert_eq!(count.get(), 29);
+    }
+    #[test]
+    #[should_panic]
+    fn test_comb_with_bad_shrink() {
+        let mut vector_in = vec![3, 2, 1];
+        comb_sort_with(&mut vector_in,
+            &CombConfig {shrink: (1, 1), ..CombConfig::default()});
+    }
+    #[test]
     fn test_comb_empty() {
         let mut vector_in:Vec<i32> = vec![];
         comb_sort(&mut vector_in);
@@ -70,4 +160,18 @@ mod tests {
         comb_sort(&mut vector_in);
         debug_assert_eq!(vector_in, vec![1]);
     }
+
+    /// Counts comparisons.
+    struct Counted<'a>(i32, &'a std::cell::Cell<usize>);
+
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+
+    impl PartialOrd for Counted<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.1.set(self.1.get() + 1);
+            self.0.partial_cmp(&other.0)
+        }
+    }
 }
\ No newline at end of file
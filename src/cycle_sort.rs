@@ -17,18 +17,14 @@
 /// sorting_rs::cycle_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn cycle_sort<T: PartialOrd + Copy>(input: &mut [T]) {
     if input.len() < 2 {return;}
     
     let in_len = input.len();
     for index in 0..in_len {
         let mut key = input[index];
-        let mut pos = index;
-
-        for i in index + 1..in_len {
-            if input[i] < key {pos += 1;}
-        }
+        let mut pos = index +
+            input[index + 1..].iter().filter(|&&x| x < key).count();
 
         if pos == index {continue;}
 
@@ -36,21 +32,15 @@ pub fn cycle_sort<T: PartialOrd + Copy>(input: &mut [T]) {
             pos += 1;
         }
         // One does not simply swap by indexes, but copies item into buffer
-        let tmp = input[pos];
-        input[pos] = key;
-        key = tmp;
+        std::mem::swap(&mut input[pos], &mut key);
 
         while pos != index {
-            pos = index;
-            for i in index + 1..in_len {
-                if input[i] < key {pos += 1;}
-            }
+            pos = index +
+                input[index + 1..].iter().filter(|&&x| x < key).count();
             while key == input[pos] {
                 pos += 1;
             }
-            let tmp = input[pos];
-            input[pos] = key;
-            key = tmp;
+            std::mem::swap(&mut input[pos], &mut key);
         }
     }
 }
@@ -0,0 +1,288 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::TryReserveError;
+use std::fmt;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Sorts a slice with any sort of the crate and checks that the result is
+/// sorted, returning [`SortError`] instead of silently leaving the slice
+/// unsorted.
+/// All kinds of slices can be checked as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+///
+/// Sorts assume that elements are totally ordered. Comparisons, which are not
+/// transitive or change between calls, and incomparable elements, like NaNs,
+/// leave the slice in some order, which sorts don't detect. The check takes
+/// `n - 1` comparisons of neighbours, every pair must be less or equal. The
+/// slice keeps all its elements in any case.
+///
+/// `try_sort_with_budget` additionally limits the number of comparisons, so
+/// quadratic sorts or expensive comparisons can't stall the caller. Elements
+/// are passed to the sort wrapped into [`Budgeted`], which counts comparisons
+/// on the current thread. The comparison over the budget unwinds out of the
+/// sort with a private payload, which is caught, and `BudgetExceeded` is
+/// returned. Sorts of the crate keep all elements in the slice when a
+/// comparison panics. Other panics of the sort are resumed, and with
+/// `panic = "abort"` running out of the budget aborts the process.
+///
+/// Fallible versions of sorts, which allocate or have a length limit, are
+/// `try_merge_sort`, `try_merge_bottom_up_sort`, `try_weak_heap_sort` and
+/// `try_bitonic_sort`. They reserve memory with `try_reserve` and report
+/// failed allocations and too long slices, and check the result like
+/// `try_sort`.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5.0, 3.0, 2.0, 4.0];
+/// sorting_rs::try_sort(&mut vec, sorting_rs::quick_sort).unwrap();
+/// assert_eq!(vec, &[2.0, 3.0, 4.0, 5.0]);
+/// ```
+/// ```rust
+/// use sorting_rs::SortError;
+///
+/// let mut vec = vec![2.0, f64::NAN, 1.0];
+/// let result = sorting_rs::try_sort(&mut vec, sorting_rs::insertion_sort);
+/// assert_eq!(result, Err(SortError::InconsistentOrder {index: 0}));
+/// ```
+/// ```rust
+/// use sorting_rs::SortError;
+///
+/// // Quicksort pivoting on the last element is quadratic on sorted input
+/// let mut vec: Vec<u32> = (0..1000).collect();
+/// let result = sorting_rs::try_sort_with_budget(&mut vec, 10_000,
+///     sorting_rs::quick_sort);
+/// assert_eq!(result, Err(SortError::BudgetExceeded {budget: 10_000}));
+/// ```
+pub fn try_sort<T: PartialOrd, F: FnOnce(&mut [T])>(input: &mut [T], sort: F)
+-> Result<(), SortError> {
+    sort(input);
+    check_sorted(input)
+}
+
+pub fn try_sort_with_budget<T: PartialOrd, F: FnOnce(&mut [Budgeted<T>])>(
+input: &mut [T], budget: usize, sort: F) -> Result<(), SortError> {
+    let result = {
+        let _guard = BudgetGuard::new(budget);
+        // SAFETY: `Budgeted<T>` is a transparent wrapper of `T`, so slices
+        // of them have the same layout
+        let wrapped = unsafe {
+            &mut *(input as *mut [T] as *mut [Budgeted<T>])
+        };
+        panic::catch_unwind(AssertUnwindSafe(|| sort(wrapped)))
+    };
+    match result {
+        Ok(()) => check_sorted(input),
+        Err(payload) if payload.is::<BudgetExhausted>() => {
+            Err(SortError::BudgetExceeded {budget})
+        },
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Error of fallible `try_` sorts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortError {
+    /// Memory for a buffer couldn't be reserved. The slice is unchanged.
+    AllocationFailed(TryReserveError),
+    /// The slice is longer than `max`, the largest length the sort supports.
+    /// The slice is unchanged.
+    TooLarge {len: usize, max: usize},
+    /// The slice isn't sorted after the sort, because comparisons are
+    /// inconsistent or some elements are incomparable. `index` is the first
+    /// element, which isn't less or equal to the next one.
+    InconsistentOrder {index: usize},
+    /// The sort needed more comparisons than `budget`, the slice is left
+    /// partially sorted.
+    BudgetExceeded {budget: usize},
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortError::AllocationFailed(error) => {
+                write!(f, "buffer allocation failed: {}", error)
+            },
+            SortError::TooLarge {len, max} => {
+                write!(f, "slice of {} elements is longer than {}", len, max)
+            },
+            SortError::InconsistentOrder {index} => {
+                write!(f, "inconsistent comparisons, element {} is out of \
+                    order", index)
+            },
+            SortError::BudgetExceeded {budget} => {
+                write!(f, "sort needed more than {} comparisons", budget)
+            },
+        }
+    }
+}
+
+impl std::error::Error for SortError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SortError::AllocationFailed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for SortError {
+    fn from(error: TryReserveError) -> Self {
+        SortError::AllocationFailed(error)
+    }
+}
+
+/// Checks that every element is less or equal to the next one.
+pub(crate) fn check_sorted<T: PartialOrd>(input: &[T])
+-> Result<(), SortError> {
+    match input.windows(2).position(|pair| pair[0].partial_cmp(&pair[1])
+        .is_none_or(|order| order == Ordering::Greater)) {
+        Some(index) => Err(SortError::InconsistentOrder {index}),
+        None => Ok(()),
+    }
+}
+
+/// Element sorted by `try_sort_with_budget`, which counts its comparisons
+/// against the budget of the current thread. It can't be sent to other
+/// threads, so parallel sorts can't be used with a budget. Comparing it
+/// outside of `try_sort_with_budget` panics.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+pub struct Budgeted<T>(T, PhantomData<*const ()>);
+
+impl<T> Budgeted<T> {
+    /// Returns the wrapped element.
+    pub fn get(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: PartialOrd> PartialEq for Budgeted<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Budgeted<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        take_comparison();
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+thread_local! {
+    /// Comparisons left on the current thread, `None` outside of
+    /// `try_sort_with_budget`.
+    static BUDGET: Cell<Option<usize>> = const {Cell::new(None)};
+}
+
+/// Panic payload, which stops a sort over the budget.
+struct BudgetExhausted;
+
+/// Counts one comparison, unwinds out of the sort if none are left.
+fn take_comparison() {
+    let left = BUDGET.with(|budget| {
+        let left = budget.get().filter(|&left| left > 0);
+        budget.set(left.map(|left| left - 1));
+        left
+    });
+    // `resume_unwind` doesn't call the panic hook, so nothing is printed
+    if left.is_none() {panic::resume_unwind(Box::new(BudgetExhausted));}
+}
+
+/// Sets the budget of the current thread and restores the previous one when
+/// dropped, also during a panic, so nested budgeted sorts don't interfere.
+struct BudgetGuard {
+    previous: Option<usize>,
+}
+
+impl BudgetGuard {
+    fn new(budget: usize) -> Self {
+        BudgetGuard {previous: BUDGET.with(|b| b.replace(Some(budget)))}
+    }
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        BUDGET.with(|b| b.set(self.previous));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_sort() {
+        let mut vector_in = vec![10, 20, 11, 24];
+        debug_assert_eq!(try_sort(&mut vector_in, crate::heap_sort), Ok(()));
+        debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
+    }
+    #[test]
+    fn test_try_sort_inconsistent() {
+        let mut vector_in = vec![1.0, 3.0, f64::NAN, 2.0];
+        debug_assert_eq!(try_sort(&mut vector_in, crate::insertion_sort),
+            Err(SortError::InconsistentOrder {index: 1}));
+        debug_assert_eq!(vector_in[..2], [1.0, 3.0]);
+        debug_assert!(vector_in[2].is_nan());
+    }
+    #[test]
+    fn test_try_sort_with_budget() {
+        let input: Vec<u32> = (0..1000).map(|x| x * 7919 % 1009).collect();
+        let mut expected = input.clone();
+        expected.sort();
+
+        let mut vector_in = input.clone();
+        debug_assert_eq!(try_sort_with_budget(&mut vector_in, 100_000,
+            crate::merge_sort), Ok(()));
+        debug_assert_eq!(vector_in, expected);
+
+        let mut vector_in = input;
+        debug_assert_eq!(try_sort_with_budget(&mut vector_in, 1000,
+            crate::insertion_sort),
+            Err(SortError::BudgetExceeded {budget: 1000}));
+        vector_in.sort();
+        debug_assert_eq!(vector_in, expected);
+    }
+    #[test]
+    fn test_budget_stops_sort() {
+        let mut vector_in = vec![1, 2];
+        debug_assert_eq!(try_sort_with_budget(&mut vector_in, 1000, |input| {
+            loop {let _ = input[0] < input[1];}
+        }), Err(SortError::BudgetExceeded {budget: 1000}));
+        debug_assert_eq!(vector_in, vec![1, 2]);
+    }
+    #[test]
+    #[should_panic(expected = "Sort panicked")]
+    fn test_budget_resumes_panic() {
+        let _ = try_sort_with_budget(&mut [1, 2], 1000, |_| {
+            panic!("Sort panicked");
+        });
+    }
+    #[test]
+    fn test_budget_nested() {
+        let mut outer = vec![3, 1, 2];
+        let result = try_sort_with_budget(&mut outer, 100, |input| {
+            let mut inner: Vec<u32> = (0..100).rev().collect();
+            debug_assert!(try_sort_with_budget(&mut inner, 10,
+                crate::insertion_sort).is_err());
+            crate::insertion_sort(input);
+        });
+        debug_assert_eq!(result, Ok(()));
+        debug_assert_eq!(outer, vec![1, 2, 3]);
+    }
+    #[test]
+    fn test_try_sort_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        debug_assert_eq!(try_sort_with_budget(&mut vector_in, 0,
+            crate::quick_sort), Ok(()));
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_try_sort_len1() {
+        let mut vector_in = vec![1];
+        debug_assert_eq!(try_sort_with_budget(&mut vector_in, 0,
+            crate::quick_sort), Ok(()));
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::insertion_sort::insert_tail;
+
 /// Sorts a slice in-place using
 /// [Gnome sort](https://en.wikipedia.org/wiki/Gnome_sort).
 /// All kinds of slices can be sorted as long as they implement
@@ -7,8 +9,10 @@
 /// understand and not very efficient. It is based on how a gnome would sort
 /// flower pots.
 /// 
-/// Upgraded version of algorithm just remember last sort position, so it
-/// doesn't try to sort already sorted data.
+/// The gnome carries an out of order pot back, moving greater pots up one by
+/// one instead of swapping, until it fits, and then walks forward past the
+/// pots it moved. Upgraded version of algorithm just remember last sort
+/// position, so it doesn't try to sort already sorted data.
 ///
 /// # Examples
 /// ```rust
@@ -31,42 +35,24 @@
 /// sorting_rs::gnome_up_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn gnome_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
     
-    let mut i = 0;
+    let mut i = 1;
     
     while i < input.len() {
-        if i == 0 || input[i - 1] < input[i] {
-            i += 1;
-        } else {
-            input.swap(i - 1, i);
-            i -= 1;
+        if input[i - 1] > input[i] {
+            i = insert_tail(input, i, 1);
         }
+        i += 1;
     }
 }
 
 pub fn gnome_up_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
-    let mut i = 1;
-    let mut j = 2;
-    let in_len = input.len();
-
-    while i < in_len {
-        if input[i - 1] <= input[i] {
-            i = j;
-            j += 1;
-        }
-        else {
-            input.swap(i - 1, i);
-            i -= 1;
-            if i == 0 {
-                i = j;
-                j += 1;
-            }
-        }
+    for j in 1..input.len() {
+        insert_tail(input, j, 1);
     }
 }
 
@@ -81,6 +67,12 @@ mod tests {
         debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
     }
     #[test]
+    fn test_gnome_duplicates() {
+        let mut vector_in = vec![3, 1, 3, 2, 1, 3];
+        gnome_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 1, 2, 3, 3, 3]);
+    }
+    #[test]
     fn test_gnome_empty() {
         let mut vector_in:Vec<i32> = vec![];
         gnome_sort(&mut vector_in);
@@ -99,6 +91,12 @@ mod tests {
         debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
     }
     #[test]
+    fn test_gnome_up_duplicates() {
+        let mut vector_in = vec![3, 1, 3, 2, 1, 3];
+        gnome_up_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 1, 2, 3, 3, 3]);
+    }
+    #[test]
     fn test_gnome_up_empty() {
         let mut vector_in:Vec<i32> = vec![];
         gnome_up_sort(&mut vector_in);
@@ -1,3 +1,5 @@
+use crate::error::{check_sorted, SortError};
+
 /// Sorts a slice in-place using
 /// [Heap sort](https://en.wikipedia.org/wiki/Heapsort),
 /// [Bottom-up heap sort](https://en.wikipedia.org/wiki/Heapsort#Bottom-up_heapsort),
@@ -21,6 +23,12 @@
 /// Weak-heap sort main aim is to minimize amount of comparisons between
 /// elements. Amount of comparisons is basically lowered down to nearly
 /// nlogn - n / ln2 + O(logn).
+/// It needs one additional bit per element, bits are packed densely into
+/// words. `weak_heap_sort_with_buffer` takes the words from the caller, so it
+/// can be used without allocator. Children of element `x` are at `2 * x` and
+/// `2 * x + 1`, so slices longer than `usize::MAX / 2` can't be sorted.
+/// `try_weak_heap_sort` returns [`SortError`] if the slice is too long, the
+/// words can't be allocated or comparisons turn out to be inconsistent.
 ///
 /// # Examples
 /// ```rust
@@ -53,7 +61,12 @@
 /// sorting_rs::weak_heap_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// let mut vec = vec![5, 2, 7, 3, 9];
+/// let mut buffer = [0; sorting_rs::weak_heap_buffer_len(5)];
+/// sorting_rs::weak_heap_sort_with_buffer(&mut vec, &mut buffer);
+/// assert_eq!(vec, &[2, 3, 5, 7, 9]);
+/// ```
 pub fn heap_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -141,46 +154,254 @@ pub fn weak_heap_sort<T: PartialOrd>(input: &mut [T]) {
     let n = input.len();
 
     if n < 2 {return;}
-    else {
-        let mut r = vec![0; (n + 7) / 8];
-        for i in (1..n).rev() {
-            let mut j = i;
-            while j & 1 == get_flag(&r, j >> 1) {j >>= 1;}
-            let gparent = j >> 1;
-            weak_heap_merge(input, &mut r, gparent, i);
-        }
 
-        for i in (2..n).rev() {
-            input.swap(0, i);
-            let mut x = 1;
-            let mut y = 2 * x + get_flag(&r, x);
-            while y < i {
-                x = y;
-                y = 2 * x + get_flag(&r, x);
-            }
-            while x > 0 {
-                weak_heap_merge(input, &mut r, 0, x);
-                x >>= 1;
-            }
+    let mut r = vec![0; weak_heap_buffer_len(n)];
+    weak_heap_sort_with_buffer(input, &mut r);
+}
+
+pub fn try_weak_heap_sort<T: PartialOrd>(input: &mut [T])
+-> Result<(), SortError> {
+    let n = input.len();
+
+    if n < 2 {return Ok(());}
+    if n > WEAK_HEAP_MAX_LEN {
+        return Err(SortError::TooLarge {len: n, max: WEAK_HEAP_MAX_LEN});
+    }
+
+    let mut r = Vec::new();
+    r.try_reserve_exact(weak_heap_buffer_len(n))?;
+    r.resize(weak_heap_buffer_len(n), 0);
+    weak_heap_sort_with_buffer(input, &mut r);
+    check_sorted(input)
+}
+
+/// Sorts a slice using caller-provided buffer for the reverse bits, so no
+/// allocation is made. Buffer must have at least `weak_heap_buffer_len(n)`
+/// words for a slice of length `n`, its contents are overwritten.
+pub fn weak_heap_sort_with_buffer<T: PartialOrd>(input: &mut [T],
+buffer: &mut [usize]) {
+    let n = input.len();
+    assert!(n <= WEAK_HEAP_MAX_LEN, "Array is too big");
+    assert!(buffer.len() >= weak_heap_buffer_len(n), "Buffer is too small");
+
+    if n < 2 {return;}
+
+    let r = &mut buffer[..weak_heap_buffer_len(n)];
+    r.fill(0);
+    weak_heapify(input, r);
+    weak_heap_sort_heap(input, r);
+}
+
+/// Largest length supported by weak heapsort, indices of children must fit
+/// into `usize`.
+const WEAK_HEAP_MAX_LEN: usize = usize::MAX / 2;
+
+/// Returns number of words needed for the reverse bits of weak heap with `n`
+/// elements, one bit per element.
+pub const fn weak_heap_buffer_len(n: usize) -> usize {
+    n.div_ceil(usize::BITS as usize)
+}
+
+/// Builds a binary max-heap in-place, which is the same heap as used by
+/// `heap_sort` and `heap_bottom_up_sort`.
+pub fn make_heap<T: PartialOrd>(input: &mut [T]) {
+    if input.len() < 2 {return;}
+
+    let in_len = input.len();
+    for start in (0..=(in_len - 2) / 2).rev() {
+        hbu_sift(input, start, in_len - 1);
+    }
+}
+
+/// Adds the last element of a slice to the max-heap formed by other elements.
+pub fn push_heap<T: PartialOrd>(input: &mut [T]) {
+    if input.is_empty() {return;}
+
+    let mut i = input.len() - 1;
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if input[parent] < input[i] {
+            input.swap(parent, i);
+            i = parent;
+        } else {break;}
+    }
+}
+
+/// Moves the largest element of a max-heap to the end of a slice, the rest
+/// remains a max-heap.
+pub fn pop_heap<T: PartialOrd>(input: &mut [T]) {
+    if input.len() < 2 {return;}
+
+    let end = input.len() - 1;
+    input.swap(0, end);
+    if end > 1 {hbu_sift(input, 0, end - 1);}
+}
+
+/// Checks if a slice is a max-heap.
+pub fn is_heap<T: PartialOrd>(input: &[T]) -> bool {
+    (1..input.len()).all(|i| input[i] <= input[(i - 1) / 2])
+}
+
+/// Max-priority queue based on
+/// [weak heap](https://en.wikipedia.org/wiki/Weak_heap).
+///
+/// Weak heap needs less comparisons than binary heap: nearly
+/// `log`<sub>`2`</sub>`n` for removal of the largest element and constant
+/// number on average for insertion. Every element has an additional reverse
+/// bit, which tells if its subtrees are swapped.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::WeakHeap;
+///
+/// let mut heap = WeakHeap::from_vec(vec![5, 2, 7]);
+/// heap.push(9);
+/// assert_eq!(heap.peek(), Some(&9));
+/// assert_eq!(heap.pop(), Some(9));
+/// assert_eq!(heap.into_sorted_vec(), &[2, 5, 7]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeakHeap<T> {
+    data: Vec<T>,
+    r: Vec<usize>,
+}
+
+impl<T: PartialOrd> WeakHeap<T> {
+    pub fn new() -> Self {
+        Self { data: Vec::new(), r: Vec::new() }
+    }
+
+    /// Builds heap from a vector in linear time.
+    pub fn from_vec(mut data: Vec<T>) -> Self {
+        let mut r = vec![0; weak_heap_buffer_len(data.len())];
+        weak_heapify(&mut data, &mut r);
+        Self { data, r }
+    }
+
+    pub fn push(&mut self, item: T) {
+        let n = self.data.len();
+        self.data.push(item);
+        self.r.resize(weak_heap_buffer_len(n + 1), 0);
+
+        // New node is a leaf, and it has to be the left child of its parent
+        // if it is the first one
+        clear_flag(&mut self.r, n);
+        if n & 1 == 0 {clear_flag(&mut self.r, n >> 1);}
+        weak_heap_sift_up(&mut self.data, &mut self.r, n);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let n = self.data.len();
+        if n == 0 {return None;}
+
+        self.data.swap(0, n - 1);
+        let item = self.data.pop();
+        weak_heap_sift_down(&mut self.data, &mut self.r, n - 1);
+        item
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns elements in heap order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns elements sorted in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        if self.data.len() > 1 {
+            weak_heap_sort_heap(&mut self.data, &mut self.r);
         }
-        input.swap(0, 1);
+        self.data
+    }
+}
+
+impl<T: PartialOrd> Default for WeakHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn weak_heapify<T: PartialOrd>(input: &mut [T], r: &mut [usize]) {
+    for i in (1..input.len()).rev() {
+        let gparent = distinguished_ancestor(r, i);
+        weak_heap_merge(input, r, gparent, i);
     }
 }
 
-fn weak_heap_merge<T: PartialOrd>(input: &mut [T], r: &mut Vec<usize>,
-i: usize, j: usize) {
+/// Sorts a weak heap of at least two elements.
+fn weak_heap_sort_heap<T: PartialOrd>(input: &mut [T], r: &mut [usize]) {
+    for i in (2..input.len()).rev() {
+        input.swap(0, i);
+        weak_heap_sift_down(input, r, i);
+    }
+    input.swap(0, 1);
+}
+
+/// Restores weak heap of length `n` after its root was replaced.
+fn weak_heap_sift_down<T: PartialOrd>(input: &mut [T], r: &mut [usize],
+n: usize) {
+    if n < 2 {return;}
+
+    let mut x = 1;
+    let mut y = 2 * x + get_flag(r, x);
+    while y < n {
+        x = y;
+        y = 2 * x + get_flag(r, x);
+    }
+    while x > 0 {
+        weak_heap_merge(input, r, 0, x);
+        x >>= 1;
+    }
+}
+
+/// Restores weak heap after a leaf at index `j` was added.
+fn weak_heap_sift_up<T: PartialOrd>(input: &mut [T], r: &mut [usize],
+mut j: usize) {
+    while j > 0 {
+        let i = distinguished_ancestor(r, j);
+        if !weak_heap_merge(input, r, i, j) {break;}
+        j = i;
+    }
+}
+
+/// Returns the closest ancestor which has element `j` in its right subtree.
+fn distinguished_ancestor(r: &[usize], mut j: usize) -> usize {
+    while j & 1 == get_flag(r, j >> 1) {j >>= 1;}
+    j >> 1
+}
+
+fn weak_heap_merge<T: PartialOrd>(input: &mut [T], r: &mut [usize],
+i: usize, j: usize) -> bool {
     if input[i] < input[j] {
         tog_flag(r, j);
         input.swap(i, j);
-    }
+        true
+    } else {false}
+}
+
+const WORD_BITS: usize = usize::BITS as usize;
+
+fn get_flag(r: &[usize], x: usize) -> usize {
+    (r[x / WORD_BITS] >> (x % WORD_BITS)) & 1
 }
 
-fn get_flag(r: &Vec<usize>, x: usize) -> usize {
-    (r[x >> 3] >> (x & 7)) & 1
+fn tog_flag(r: &mut [usize], x: usize) {
+    r[x / WORD_BITS] ^= 1 << (x % WORD_BITS)
 }
 
-fn tog_flag(r: &mut Vec<usize>, x: usize) {
-    r[x >> 3] ^= 1 << (x & 7)
+fn clear_flag(r: &mut [usize], x: usize) {
+    r[x / WORD_BITS] &= !(1 << (x % WORD_BITS))
 }
 
 #[cfg(test)]
@@ -236,6 +457,40 @@ mod tests {
         debug_assert_eq!(array, [5, 6, 7, 8, 9, 10, 11, 19, 20, 21, 22, 24]);
     }
     #[test]
+    fn test_weak_heap_with_buffer() {
+        let mut vector_in: Vec<i32> = (0..1000).map(|x| (x * 367) % 1000)
+            .collect();
+        let mut buffer = [usize::MAX; 40];
+        debug_assert_eq!(weak_heap_buffer_len(1000), 1000 / WORD_BITS + 1);
+        weak_heap_sort_with_buffer(&mut vector_in, &mut buffer);
+        debug_assert_eq!(vector_in, (0..1000).collect::<Vec<i32>>());
+    }
+    #[test]
+    #[should_panic]
+    fn test_weak_heap_small_buffer() {
+        let mut vector_in: Vec<i32> = (0..1000).collect();
+        weak_heap_sort_with_buffer(&mut vector_in, &mut [0; 2]);
+    }
+    #[test]
+    fn test_try_weak_heap() {
+        let mut vector_in = vec![10, 20, 11, 24];
+        debug_assert_eq!(try_weak_heap_sort(&mut vector_in), Ok(()));
+        debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
+
+        // Zero-sized elements take no memory, so any length can be made
+        let mut vector_in = vec![(); usize::MAX];
+        debug_assert_eq!(try_weak_heap_sort(&mut vector_in),
+            Err(SortError::TooLarge {len: usize::MAX, max: usize::MAX / 2}));
+
+        // The bits would take 2^60 bytes on 64-bit systems
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut vector_in = vec![(); usize::MAX / 2];
+            debug_assert!(matches!(try_weak_heap_sort(&mut vector_in),
+                Err(SortError::AllocationFailed(_))));
+        }
+    }
+    #[test]
     fn test_weak_heap_empty() {
         let mut vector_in:Vec<i32> = vec![];
         weak_heap_sort(&mut vector_in);
@@ -247,4 +502,67 @@ mod tests {
         weak_heap_sort(&mut vector_in);
         debug_assert_eq!(vector_in, vec![1]);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_heap_slice_operations() {
+        let mut vector_in = vec![10, 20, 11, 24, 22, 21, 19, 9, 7, 8, 6, 5];
+        make_heap(&mut vector_in);
+        debug_assert!(is_heap(&vector_in));
+        vector_in.push(30);
+        debug_assert!(!is_heap(&vector_in));
+        push_heap(&mut vector_in);
+        debug_assert!(is_heap(&vector_in));
+        debug_assert_eq!(vector_in[0], 30);
+
+        let mut sorted = vec![];
+        while !vector_in.is_empty() {
+            pop_heap(&mut vector_in);
+            sorted.push(vector_in.pop().unwrap());
+            debug_assert!(is_heap(&vector_in));
+        }
+        debug_assert_eq!(sorted, vec![30, 24, 22, 21, 20, 19, 11, 10, 9, 8,
+            7, 6, 5]);
+    }
+    #[test]
+    fn test_heap_slice_operations_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        make_heap(&mut vector_in);
+        push_heap(&mut vector_in);
+        pop_heap(&mut vector_in);
+        debug_assert!(is_heap(&vector_in));
+    }
+    #[test]
+    fn test_weak_heap_queue() {
+        let mut heap = WeakHeap::new();
+        let mut reference = vec![];
+        for i in 0..500 {
+            let item = (i * 367) % 113;
+            heap.push(item);
+            reference.push(item);
+            if i % 3 == 0 {
+                reference.sort();
+                debug_assert_eq!(heap.peek(), reference.last());
+                debug_assert_eq!(heap.pop(), reference.pop());
+            }
+        }
+        debug_assert_eq!(heap.len(), reference.len());
+        reference.sort();
+        while let Some(item) = heap.pop() {
+            debug_assert_eq!(Some(item), reference.pop());
+        }
+        debug_assert!(heap.is_empty());
+        debug_assert_eq!(heap.pop(), None);
+    }
+    #[test]
+    fn test_weak_heap_queue_sorted_vec() {
+        let input: Vec<i32> = (0..100).map(|x| (x * 37) % 101).collect();
+        let mut reference = input.clone();
+        reference.sort();
+        let heap = WeakHeap::from_vec(input.clone());
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+        let mut heap = WeakHeap::from_vec(input);
+        heap.push(-1);
+        reference.insert(0, -1);
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+        debug_assert_eq!(WeakHeap::from_vec(vec![1]).into_sorted_vec(), [1]);
+    }
+}
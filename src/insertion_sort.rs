@@ -1,3 +1,6 @@
+use std::mem::ManuallyDrop;
+use std::ptr;
+
 /// Sorts a slice in-place using
 /// [Insertion sort](https://en.wikipedia.org/wiki/Insertion_sort).
 /// All kinds of slices can be sorted as long as they implement
@@ -7,6 +10,16 @@
 /// This is because insertion sort has constant space complexity and works
 /// very fast when used on partially sorted data.
 /// 
+/// The inserted element is taken out of the slice, leaving a hole, and
+/// greater elements are moved into the hole one by one, so every step moves
+/// one element instead of swapping two. The element is written into the hole
+/// also if a comparison panics, so the slice always keeps all its elements.
+///
+/// `binary_insertion_sort` finds the place of the inserted element with
+/// binary search and then shifts greater elements at once, which takes
+/// `nlogn` comparisons, so it's useful when comparisons are expensive. Both
+/// sorts are stable.
+///
 /// # Examples
 /// ```rust
 /// let mut vec = vec![-4, -5, 7, 45, 0];
@@ -18,15 +31,115 @@
 /// sorting_rs::insertion_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// let mut vec = vec![-4, -5, 7, 45, 0];
+/// sorting_rs::binary_insertion_sort(&mut vec);
+/// assert_eq!(vec, &[-5, -4, 0, 7, 45]);
+/// ```
 pub fn insertion_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
     
     for i in 1..input.len() {
-        let mut j = i;
-        while j > 0 && input[j - 1] > input[j] {
-            input.swap(j - 1, j);
-            j -= 1;
+        insert_tail(input, i, 1);
+    }
+}
+
+pub fn binary_insertion_sort<T: PartialOrd>(input: &mut [T]) {
+    if input.len() < 2 {return;}
+
+    for i in 1..input.len() {
+        // Place after all elements not greater than the inserted one
+        let (mut low, mut high) = (0, i);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if input[i] < input[mid] {high = mid;} else {low = mid + 1;}
+        }
+        if low < i {
+            let mut hole = Hole::new(input, i);
+            hole.shift_from(low);
+        }
+    }
+}
+
+/// Inserts `input[i]` into the sorted sequence of elements `gap` apart before
+/// it and returns its new position.
+pub(crate) fn insert_tail<T: PartialOrd>(input: &mut [T], i: usize,
+gap: usize) -> usize {
+    if i >= gap && input[i - gap] > input[i] {
+        let mut hole = Hole::new(input, i);
+        hole.move_from(i - gap);
+        while hole.pos() >= gap && hole.get(hole.pos() - gap) > hole.element() {
+            hole.move_from(hole.pos() - gap);
+        }
+        return hole.pos();
+    }
+    i
+}
+
+/// Element taken out of a slice, which leaves a hole at its position. Other
+/// elements are moved into the hole, and the element is written into the
+/// hole, when the guard is dropped, even during a panic, so the slice never
+/// loses or duplicates an element.
+pub(crate) struct Hole<'a, T> {
+    slice: &'a mut [T],
+    element: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    /// Takes the element at `pos` out of the slice.
+    pub(crate) fn new(slice: &'a mut [T], pos: usize) -> Self {
+        // SAFETY: the element is written back when the hole is dropped, until
+        // then the copy left in the slice is never read
+        let element = unsafe {ManuallyDrop::new(ptr::read(&slice[pos]))};
+        Hole {slice, element, pos}
+    }
+
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Returns element `i` of the slice, which must not be the hole.
+    pub(crate) fn get(&self, i: usize) -> &T {
+        assert_ne!(i, self.pos);
+        &self.slice[i]
+    }
+
+    /// Moves element `i` into the hole, leaving the hole at `i`.
+    pub(crate) fn move_from(&mut self, i: usize) {
+        assert_ne!(i, self.pos);
+        assert!(i < self.slice.len());
+        // Both pointers are derived from the whole slice, so neither borrow
+        // invalidates the other
+        let base = self.slice.as_mut_ptr();
+        // SAFETY: both positions are in bounds and different, the element at
+        // `i` is left as the new hole
+        unsafe {ptr::copy_nonoverlapping(base.add(i), base.add(self.pos), 1);}
+        self.pos = i;
+    }
+
+    /// Shifts elements from `i` to the hole one position up, leaving the hole
+    /// at `i < pos`.
+    pub(crate) fn shift_from(&mut self, i: usize) {
+        assert!(i < self.pos);
+        let base = self.slice.as_mut_ptr();
+        // SAFETY: the range `i..=pos` is in bounds, the element at `pos` is
+        // the hole, so overwriting it doesn't lose anything
+        unsafe {ptr::copy(base.add(i), base.add(i + 1), self.pos - i);}
+        self.pos = i;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    fn drop(&mut self) {
+        let base = self.slice.as_mut_ptr();
+        // SAFETY: the hole is in bounds and its old content was moved out
+        unsafe {
+            ptr::copy_nonoverlapping(&*self.element, base.add(self.pos), 1);
         }
     }
 }
@@ -34,6 +147,10 @@ pub fn insertion_sort<T: PartialOrd>(input: &mut [T]) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+    use std::cmp::Ordering;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
 
     #[test]
     fn test_insertion() {
@@ -53,22 +170,90 @@ mod tests {
         insertion_sort(&mut vector_in);
         debug_assert_eq!(vector_in, vec![1]);
     }
-    // #[test]
-    // fn test_insertion_bin() {
-    //     let mut vector_in = vec![10, 20, 11, 24];
-    //     insertion_bin_sort(&mut vector_in);
-    //     debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
-    // }
-    // #[test]
-    // fn test_insertion_bin_empty() {
-    //     let mut vector_in:Vec<i32> = vec![];
-    //     insertion_bin_sort(&mut vector_in);
-    //     debug_assert_eq!(vector_in, &[]);
-    // }
-    // #[test]
-    // fn test_insertion_bin_len1() {
-    //     let mut vector_in = vec![1];
-    //     insertion_bin_sort(&mut vector_in);
-    //     debug_assert_eq!(vector_in, vec![1]);
-    // }
+    #[test]
+    fn test_insertion_bin() {
+        let mut vector_in = vec![10, 20, 11, 24];
+        binary_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
+    }
+    #[test]
+    fn test_insertion_bin_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        binary_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_insertion_bin_len1() {
+        let mut vector_in = vec![1];
+        binary_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+    #[test]
+    fn test_insertion_stable() {
+        for sort in [insertion_sort::<Key>, binary_insertion_sort::<Key>] {
+            let mut keys: Vec<Key> = (0..200).map(|i| Key(i * 37 % 11, i))
+                .collect();
+            let mut expected: Vec<(usize, usize)> = keys.iter()
+                .map(|key| (key.0, key.1)).collect();
+            expected.sort_by_key(|item| item.0);
+            sort(&mut keys);
+            let result: Vec<(usize, usize)> = keys.iter()
+                .map(|key| (key.0, key.1)).collect();
+            debug_assert_eq!(result, expected);
+        }
+    }
+    #[test]
+    fn test_insertion_panic() {
+        for sort in [insertion_sort::<Panicky>,
+            binary_insertion_sort::<Panicky>] {
+            for limit in [1, 10, 50, 150] {
+                let input: Vec<String> = (0..40).map(|i| (i * 7 % 40)
+                    .to_string()).collect();
+                let comparisons = Rc::new(Cell::new(0));
+                let mut items: Vec<Panicky> = input.iter()
+                    .map(|s| Panicky(s.clone(), comparisons.clone(), limit))
+                    .collect();
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    sort(&mut items);
+                }));
+                debug_assert!(result.is_err());
+                let mut kept: Vec<String> = items.into_iter()
+                    .map(|item| item.0).collect();
+                let mut expected = input;
+                kept.sort();
+                expected.sort();
+                debug_assert_eq!(kept, expected);
+            }
+        }
+    }
+
+    /// Compares only the first field, the second one tracks stability.
+    struct Key(usize, usize);
+
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+
+    impl PartialOrd for Key {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    /// Panics on the comparison number `limit`.
+    struct Panicky(String, Rc<Cell<usize>>, usize);
+
+    impl PartialEq for Panicky {
+        fn eq(&self, other: &Self) -> bool {
+            self.partial_cmp(other) == Some(Ordering::Equal)
+        }
+    }
+
+    impl PartialOrd for Panicky {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.1.set(self.1.get() + 1);
+            if self.1.get() == self.2 {panic!("comparison failed");}
+            self.0.partial_cmp(&other.0)
+        }
+    }
 }
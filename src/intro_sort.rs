@@ -0,0 +1,109 @@
+use crate::small_sort::{small_sort, SMALL_SORT_LEN};
+use crate::pivot::{PivotChooser, PivotStrategy};
+
+/// Sorts a slice in-place using
+/// [Introsort](https://en.wikipedia.org/wiki/Introsort)
+/// All kinds of slices can be sorted as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+///
+/// Introsort was presented by David Musser in 1997. It's a quicksort, which
+/// counts the depth of recursion and switches to heapsort when the depth
+/// exceeds `2log`<sub>2</sub>`n`, so the worst case is `nlogn` instead of
+/// quadratic, while typical input is sorted as fast as with quicksort. Small
+/// ranges are sorted by sorting networks and a branchless merge.
+///
+/// This implementation picks median of three or, for larger ranges, Tukey's
+/// ninther as pivot and uses the Hoare partition scheme, so sorted, reversed
+/// and duplicate-heavy input is split evenly.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5, 3, 2, 4];
+/// sorting_rs::intro_sort(&mut vec);
+/// assert_eq!(vec, &[2, 3, 4, 5]);
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// sorting_rs::intro_sort(&mut strings);
+/// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
+/// ```
+pub fn intro_sort<T: PartialOrd>(input: &mut [T]) {
+    intro_sort_limited(input, depth_limit(input.len()));
+}
+
+/// Returns recursion depth after which introsort switches to heapsort.
+pub(crate) fn depth_limit(len: usize) -> usize {
+    2 * (usize::BITS - len.leading_zeros()) as usize
+}
+
+pub(crate) fn intro_sort_limited<T: PartialOrd>(input: &mut [T],
+limit: usize) {
+    if input.len() <= SMALL_SORT_LEN {
+        small_sort(input);
+    } else if limit == 0 {
+        crate::heap_sort(input);
+    } else {
+        let pivot = intro_partition(input);
+        intro_sort_limited(&mut input[..pivot], limit - 1);
+        intro_sort_limited(&mut input[pivot + 1..], limit - 1);
+    }
+}
+
+/// Partitions a slice around median of three or ninther and returns final
+/// position of the pivot.
+pub(crate) fn intro_partition<T: PartialOrd>(input: &mut [T]) -> usize {
+    let strategy = if input.len() < 128 {PivotStrategy::MedianOfThree}
+        else {PivotStrategy::Ninther};
+    let pivot = PivotChooser::new(strategy).choose(input);
+    crate::partition_hoare(input, pivot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intro() {
+        let mut vector_in = vec![10, 20, 11, 24, 15];
+        intro_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 15, 20, 24]);
+    }
+    #[test]
+    fn test_intro_patterns() {
+        let inputs: Vec<Vec<i32>> = vec![
+            (0..10_000).collect(),
+            (0..10_000).rev().collect(),
+            vec![7; 10_000],
+            (0..10_000).map(|x| (x * 367) % 1000).collect(),
+            (0..10_000).map(|x| if x % 2 == 0 {x} else {10_000 - x}).collect(),
+        ];
+        for input in inputs {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut vector_in = input;
+            intro_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
+    fn test_intro_heap_fallback() {
+        let mut vector_in: Vec<i32> = (0..1000).map(|x| (x * 37) % 101)
+            .collect();
+        let mut expected = vector_in.clone();
+        expected.sort();
+        intro_sort_limited(&mut vector_in, 1);
+        debug_assert_eq!(vector_in, expected);
+    }
+    #[test]
+    fn test_intro_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        intro_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_intro_len1() {
+        let mut vector_in = vec![1];
+        intro_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::small_sort::{small_sort, SMALL_SORT_LEN};
+
 /// Sorts a slice in-place using
 /// [K-sort](https://arxiv.org/abs/1107.3622)
 /// 
@@ -5,6 +7,8 @@
 /// be faster than quicksort for arrays with less than 7 million elements
 /// This algorithm is generally compared to heapsort and quicksort, it doesn't
 /// need to construct the heap and generally wins in benchmarks because of this.
+/// The smaller part is sorted recursively and the larger one in a loop, so the
+/// stack depth is logarithmic also on sorted input, which takes quadratic time.
 /// 
 /// # Examples
 /// ```rust
@@ -17,18 +21,41 @@
 /// sorting_rs::ksort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn ksort<T: PartialOrd + Clone + Copy>(input: &mut [T]) {
     if input.len() < 2 {return;}
     ksort_lr(input, 0, input.len() - 1);
 }
 
-fn ksort_lr<T: PartialOrd + Clone + Copy>(input: &mut [T], left: usize,
-right: usize) {
-    let key = input[left].clone();
+fn ksort_lr<T: PartialOrd + Clone + Copy>(input: &mut [T], mut left: usize,
+mut right: usize) {
+    loop {
+        if right - left < SMALL_SORT_LEN {
+            small_sort(&mut input[left..=right]);
+            return;
+        }
+        let i = ksort_partition(input, left, right);
+
+        // The smaller part is sorted recursively and the larger one in the
+        // loop, so the recursion is at most log n deep
+        let (lower, upper) = ((left, i), (i + 1, right + 1));
+        let (smaller, larger) = if i - left <= right - i {(lower, upper)}
+            else {(upper, lower)};
+        if smaller.1 - smaller.0 > 1 {
+            ksort_lr(input, smaller.0, smaller.1 - 1);
+        }
+        if larger.1 - larger.0 < 2 {return;}
+        (left, right) = (larger.0, larger.1 - 1);
+    }
+}
+
+/// Puts the first element of the range into its final position and returns
+/// that position.
+fn ksort_partition<T: PartialOrd + Copy>(input: &mut [T], left: usize,
+right: usize) -> usize {
+    let key = input[left];
     // just init it, so no unsafe calls needed, otherwise use of uninit
     // is prohibited by Rust compiler
-    let mut temp = key.clone();
+    let mut temp = key;
     let mut i = left;
     let mut j = right + 1;
     let mut k = i + 1;
@@ -41,7 +68,7 @@ right: usize) {
                 input.swap(j, p);
             } else if j == right + 1 {
                 flag = true;
-                temp = input[p].clone();
+                temp = input[p];
             }
             j -= 1;
             p = j;
@@ -55,13 +82,7 @@ right: usize) {
 
     input[i] = key;
     if flag {input[i + 1] = temp;}
-
-    if left < i.saturating_sub(1) {
-        ksort_lr(input, left, i - 1);
-    }
-    if right > i + 1 {
-        ksort_lr(input, i + 1, right);
-    }
+    i
 }
 
 #[cfg(test)]
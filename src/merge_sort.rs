@@ -1,9 +1,24 @@
+use crate::error::{check_sorted, SortError};
+use crate::small_sort::{stable_small_sort, SMALL_SORT_LEN};
+use std::cmp::min;
+
 /// Sorts a slice out-of-place using
 /// [Merge sort](https://en.wikipedia.org/wiki/Merge_sort) and
 /// [Bottom Up Merge sort](https://en.wikipedia.org/wiki/Merge_sort#Bottom-up_implementation)
 /// All kinds of slices can be sorted as long as they implement
 /// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
 /// 
+/// Both sorts allocate one buffer of the slice size and merge runs between
+/// the slice and the buffer, changing direction on every level, so merged
+/// runs are never copied back. `merge_sort_with_buffer` takes the buffer
+/// from the caller, so it can be used without allocator. `try_merge_sort` and
+/// `try_merge_bottom_up_sort` return [`SortError`] if the
+/// buffer can't be allocated or comparisons turn out to be inconsistent.
+/// 
+/// Runs of up to 32 elements are sorted by the branchless kernels without
+/// networks, see `branchless_insertion_sort`, and merges take the left
+/// element of equal ones, so both sorts are stable.
+/// 
 /// # Examples
 /// ```rust
 /// let mut slice = vec![3,2,1,4];
@@ -25,57 +40,120 @@
 /// sorting_rs::merge_bottom_up_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-use std::cmp::min;
-
+/// ```rust
+/// let mut slice = vec![3,2,1,4];
+/// let mut buffer = [0; 4];
+/// sorting_rs::merge_sort_with_buffer(&mut slice, &mut buffer);
+/// assert_eq!(slice, &[1,2,3,4]);
+/// ```
 pub fn merge_sort<T: PartialOrd + Copy>(input: &mut [T]) {
-    if input.len() < 2 {return;}
-    
-    let len = input.len();
-    let mid = len / 2;
-    merge_sort(&mut input[..mid]);
-    merge_sort(&mut input[mid..]);
-
-    let mut tmp = Vec::with_capacity(len);
-    let mut i = 0;
-    let mut j = mid;
-
-    while i < mid && j < len {
-        if input[i] < input[j] {
-            tmp.push(input[i]);
-            i += 1;
-        } else {
-            tmp.push(input[j]);
-            j += 1;
-        }
+    if input.len() <= SMALL_SORT_LEN {
+        stable_small_sort(input);
+        return;
     }
-    if i < mid {
-        tmp.extend_from_slice(&input[i..mid]);
-    } else if j < len {
-        tmp.extend_from_slice(&input[j..len]);
+
+    let mut buffer = input.to_vec();
+    merge_sort_with_buffer(input, &mut buffer);
+}
+
+pub fn try_merge_sort<T: PartialOrd + Copy>(input: &mut [T])
+-> Result<(), SortError> {
+    if input.len() <= SMALL_SORT_LEN {
+        stable_small_sort(input);
+    } else {
+        let mut buffer = try_buffer(input)?;
+        merge_sort_with_buffer(input, &mut buffer);
     }
+    check_sorted(input)
+}
 
-    input.copy_from_slice(&tmp[..]);
+/// Sorts a slice using caller-provided scratch buffer, so no allocation is
+/// made. Buffer must have at least as many elements as the slice, its
+/// contents are overwritten.
+pub fn merge_sort_with_buffer<T: PartialOrd + Copy>(input: &mut [T],
+buffer: &mut [T]) {
+    assert!(buffer.len() >= input.len(), "Buffer is too small");
+    let len = input.len();
+    sort_into(input, &mut buffer[..len], false);
+}
+
+/// Sorts `input` and leaves the result in `buffer` if `to_buffer` is set, or
+/// in `input` otherwise. Halves are sorted into the other slice, so every
+/// level merges in the opposite direction and nothing is copied back.
+fn sort_into<T: PartialOrd + Copy>(input: &mut [T], buffer: &mut [T],
+to_buffer: bool) {
+    if input.len() <= SMALL_SORT_LEN {
+        stable_small_sort(input);
+        if to_buffer {buffer.copy_from_slice(input);}
+        return;
+    }
+
+    let mid = input.len() / 2;
+    let (input_left, input_right) = input.split_at_mut(mid);
+    let (buffer_left, buffer_right) = buffer.split_at_mut(mid);
+    sort_into(input_left, buffer_left, !to_buffer);
+    sort_into(input_right, buffer_right, !to_buffer);
+
+    if to_buffer {
+        merge(&input[..mid], &input[mid..], buffer);
+    } else {
+        merge(&buffer[..mid], &buffer[mid..], input);
+    }
 }
 
 pub fn merge_bottom_up_sort<T: PartialOrd + Copy>(input: &mut [T]) {
-    let mut width = 1;
+    if input.len() <= SMALL_SORT_LEN {
+        stable_small_sort(input);
+        return;
+    }
 
-    let mut tmp = input.to_vec();
-    let len = input.len();
+    let mut buffer = input.to_vec();
+    bottom_up(input, &mut buffer);
+}
 
-    while width < len {
-        let mut i = 0;
-        while i < len {
-            let start = min(i + 2 * width, len);
-            let mid = min(i + width, len);
+pub fn try_merge_bottom_up_sort<T: PartialOrd + Copy>(input: &mut [T])
+-> Result<(), SortError> {
+    if input.len() <= SMALL_SORT_LEN {
+        stable_small_sort(input);
+    } else {
+        let mut buffer = try_buffer(input)?;
+        bottom_up(input, &mut buffer);
+    }
+    check_sorted(input)
+}
 
-            merge(&input[i..mid], &input[mid..start], &mut tmp[i..start]);
-            input[i..start].copy_from_slice(&tmp[i..start]);
+/// Copies the slice into a new buffer, reporting a failed allocation instead
+/// of aborting.
+fn try_buffer<T: Copy>(input: &[T]) -> Result<Vec<T>, SortError> {
+    let mut buffer = Vec::new();
+    buffer.try_reserve_exact(input.len())?;
+    buffer.extend_from_slice(input);
+    Ok(buffer)
+}
 
-            i += 2 * width;
+/// Sorts runs of `SMALL_SORT_LEN` elements and merges them between the
+/// slice and the buffer of the same length.
+fn bottom_up<T: PartialOrd + Copy>(input: &mut [T], buffer: &mut [T]) {
+    for chunk in input.chunks_mut(SMALL_SORT_LEN) {
+        stable_small_sort(chunk);
+    }
+    let len = input.len();
+    let mut from: &mut [T] = input;
+    let mut to: &mut [T] = buffer;
+    let mut moved = false;
+    let mut width = SMALL_SORT_LEN;
+    while width < len {
+        for (source, target) in from.chunks(2 * width)
+            .zip(to.chunks_mut(2 * width)) {
+            let mid = min(width, source.len());
+            merge(&source[..mid], &source[mid..], target);
         }
+        std::mem::swap(&mut from, &mut to);
+        moved = !moved;
         width *= 2;
     }
+    // After an odd number of passes sorted elements are in the buffer
+    if moved {to.copy_from_slice(from);}
 }
 
 fn merge<T: PartialOrd + Copy>(in1: &[T], in2: &[T], tmp: &mut [T]) {
@@ -106,6 +184,21 @@ fn merge<T: PartialOrd + Copy>(in1: &[T], in2: &[T], tmp: &mut [T]) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cmp::Ordering;
+
+    /// Compares only the key, the index tracks stability.
+    #[derive(Clone, Copy, Debug)]
+    struct K(u32, usize);
+
+    impl PartialEq for K {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+
+    impl PartialOrd for K {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
 
     #[test]
     fn test_merge() {
@@ -114,6 +207,66 @@ mod tests {
         debug_assert_eq!(vector_in, vec![10, 11, 13, 20, 24]);
     }
     #[test]
+    fn test_merge_sizes() {
+        for len in [33, 64, 100, 1000, 4097] {
+            let input: Vec<u32> = (0..len).map(|x| x * 7919 % 1009).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let mut vector_in = input.clone();
+            merge_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+            let mut vector_in = input;
+            merge_bottom_up_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
+    fn test_merge_stable() {
+        for len in [10, 16, 17, 32, 33, 100, 1000] {
+            let input: Vec<K> = (0..len).map(|i| K(i as u32 * 7919 % 7, i))
+                .collect();
+            for sort in [merge_sort::<K>, merge_bottom_up_sort] {
+                let mut vector_in = input.clone();
+                sort(&mut vector_in);
+                debug_assert!(vector_in.windows(2).all(|w| w[0].0 < w[1].0
+                    || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+            }
+        }
+    }
+    #[test]
+    fn test_merge_with_buffer() {
+        let mut vector_in: Vec<u32> = (0..500).map(|x| x * 7919 % 211)
+            .collect();
+        let mut expected = vector_in.clone();
+        expected.sort();
+        let mut buffer = vec![0; 600];
+        merge_sort_with_buffer(&mut vector_in, &mut buffer);
+        debug_assert_eq!(vector_in, expected);
+    }
+    #[test]
+    #[should_panic(expected = "Buffer is too small")]
+    fn test_merge_with_small_buffer() {
+        let mut vector_in = vec![3, 2, 1];
+        merge_sort_with_buffer(&mut vector_in, &mut [0; 2]);
+    }
+    #[test]
+    fn test_try_merge() {
+        for len in [5, 100] {
+            let input: Vec<i32> = (0..len).map(|x| x * 7919 % 101).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            for sort in [try_merge_sort::<i32>, try_merge_bottom_up_sort] {
+                let mut vector_in = input.clone();
+                debug_assert_eq!(sort(&mut vector_in), Ok(()));
+                debug_assert_eq!(vector_in, expected);
+            }
+        }
+        let mut vector_in = vec![f64::NAN; 100];
+        vector_in[50] = 1.0;
+        debug_assert!(matches!(try_merge_sort(&mut vector_in),
+            Err(SortError::InconsistentOrder {..})));
+    }
+    #[test]
     fn test_merge_empty() {
         let mut vector_in:Vec<i32> = vec![];
         merge_sort(&mut vector_in);
@@ -0,0 +1,672 @@
+use crate::network_sort::compare_and_swap;
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use std::sync::{Condvar, Mutex};
+
+/// [Sorting network](https://en.wikipedia.org/wiki/Sorting_network) as a
+/// value, which can be built, inspected, verified, drawn and executed.
+///
+/// Network is a list of comparators `(i, j)` with `i < j`, each of them
+/// puts the smaller of elements at positions `i` and `j` to `i`. Comparators
+/// are grouped into layers, comparators of one layer touch distinct
+/// positions, so they can be executed at the same time. Number of layers is
+/// the depth of the network, number of comparators is its size.
+///
+/// Builders are provided for classic networks of any number of inputs:
+/// - `bitonic`: Batcher's bitonic sorter, halves are merged as one bitonic
+///   sequence, so every merge layer compares elements at the same distance;
+/// - `batcher`: Batcher's odd-even merge sort in the merge exchange form from
+///   Knuth's "The Art of Computer Programming", it needs less comparators
+///   than the bitonic one;
+/// - `pairwise`: Parberry's pairwise sorting network, which has the same size
+///   and depth as the odd-even one, but sorts pairs first and then merges
+///   them in a different order;
+/// - `insertion`: insertion sort as a network, `n(n - 1) / 2` comparators
+///   with depth `2n - 3`;
+/// - `optimal`: networks of `network_sort` for up to 16 inputs.
+///
+/// Networks of lengths other than powers of two are built by removing the
+/// comparators touching the positions above the length from the network for
+/// the next power of two: those positions would hold the largest elements
+/// and would never be moved.
+///
+/// `is_sorting` verifies a network using 0-1 principle: network sorts all
+/// inputs if it sorts all `2`<sup>`n`</sup> sequences of zeros and ones.
+/// The sequences are checked 64 at a time as bits of machine words, but the
+/// time still grows exponentially, so it's practical for up to about 30
+/// inputs.
+///
+/// `to_svg` draws a network as Knuth diagram: horizontal lines are the
+/// positions, vertical lines with dots are comparators. `to_dot` exports it to
+/// [Graphviz](https://graphviz.org/) format, where comparators are the nodes
+/// and positions are the edges between them.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::network::Network;
+///
+/// let network = Network::batcher(6);
+/// assert_eq!(network.size(), 12);
+/// assert!(network.is_sorting());
+///
+/// let mut vec = vec![5, 3, 2, 4, 1, 6];
+/// network.apply(&mut vec);
+/// assert_eq!(vec, &[1, 2, 3, 4, 5, 6]);
+/// ```
+/// ```rust
+/// use sorting_rs::network::Network;
+///
+/// let network = Network::from_comparators(3, &[(0, 1), (1, 2), (0, 1)]);
+/// assert_eq!(network.depth(), 3);
+/// assert!(network.is_sorting());
+/// assert!(network.to_svg().starts_with("<svg"));
+/// assert!(network.to_dot().starts_with("digraph"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    inputs: usize,
+    layers: Vec<Vec<(usize, usize)>>,
+}
+
+impl Network {
+    /// Returns network without comparators.
+    pub fn new(inputs: usize) -> Self {
+        Self { inputs, layers: Vec::new() }
+    }
+
+    /// Builds network from comparators in the order of execution, every
+    /// comparator is placed to the first layer after the ones using its
+    /// positions.
+    pub fn from_comparators(inputs: usize, comparators: &[(usize, usize)])
+    -> Self {
+        let mut network = Self::new(inputs);
+        for &(i, j) in comparators {
+            network.push(i, j);
+        }
+        network
+    }
+
+    /// Adds comparator to the end of the network.
+    pub fn push(&mut self, i: usize, j: usize) {
+        assert!(i < j && j < self.inputs, "Invalid comparator");
+
+        // Layer is searched from the end, as comparators can't be moved before
+        // the ones using the same positions
+        let mut layer = self.layers.len();
+        while layer > 0 && self.layers[layer - 1].iter()
+            .all(|&(a, b)| a != i && a != j && b != i && b != j) {
+            layer -= 1;
+        }
+        if layer == self.layers.len() {
+            self.layers.push(Vec::new());
+        }
+        self.layers[layer].push((i, j));
+    }
+
+    pub fn bitonic(n: usize) -> Self {
+        let mut network = Self::new(n);
+        bitonic_comparators(n, |i, j| network.push(i, j));
+        network
+    }
+
+    pub fn batcher(n: usize) -> Self {
+        let mut network = Self::new(n);
+        batcher_comparators(n, |i, j| network.push(i, j));
+        network
+    }
+
+    pub fn pairwise(n: usize) -> Self {
+        let mut network = Self::new(n);
+        pairwise_comparators(n, |i, j| network.push(i, j));
+        network
+    }
+
+    pub fn insertion(n: usize) -> Self {
+        let mut network = Self::new(n);
+        for i in 1..n {
+            for j in (0..i).rev() {
+                network.push(j, j + 1);
+            }
+        }
+        network
+    }
+
+    /// Returns network used by `network_sort` for `n <= 16` inputs.
+    pub fn optimal(n: usize) -> Option<Self> {
+        crate::network_sort::NETWORKS.get(n)
+            .map(|comparators| Self::from_comparators(n, comparators))
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn layers(&self) -> &[Vec<(usize, usize)>] {
+        &self.layers
+    }
+
+    /// Returns comparators in the order of execution.
+    pub fn comparators(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.layers.iter().flatten().copied()
+    }
+
+    pub fn size(&self) -> usize {
+        self.layers.iter().map(Vec::len).sum()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Executes network on a slice of length equal to the number of inputs.
+    pub fn apply<T: PartialOrd>(&self, input: &mut [T]) {
+        assert_eq!(input.len(), self.inputs,
+            "Slice length must be equal to the number of inputs");
+
+        for (i, j) in self.comparators() {
+            compare_and_swap(input, i, j);
+        }
+    }
+
+    pub fn is_sorting(&self) -> bool {
+        let n = self.inputs;
+        assert!(n < 64, "Network is too big to be verified");
+
+        // Bit `k` of word `c` is the value at position `c` of sequence
+        // `base + k`, patterns of the first 6 positions are the same for
+        // every block of 64 sequences
+        const PATTERNS: [u64; 6] = [
+            0xaaaa_aaaa_aaaa_aaaa, 0xcccc_cccc_cccc_cccc, 0xf0f0_f0f0_f0f0_f0f0,
+            0xff00_ff00_ff00_ff00, 0xffff_0000_ffff_0000, 0xffff_ffff_0000_0000,
+        ];
+        let sequences = 1u64 << n;
+        let mask = if n < 6 {(1u64 << sequences) - 1} else {u64::MAX};
+        let mut words = vec![0u64; n];
+
+        for base in (0..sequences).step_by(64) {
+            for (c, word) in words.iter_mut().enumerate() {
+                *word = if c < 6 {PATTERNS[c]}
+                    else if (base >> c) & 1 == 1 {u64::MAX}
+                    else {0};
+            }
+            for (i, j) in self.comparators() {
+                let (a, b) = (words[i], words[j]);
+                words[i] = a & b;
+                words[j] = a | b;
+            }
+            if words.windows(2).any(|w| w[0] & !w[1] & mask != 0) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns Knuth diagram of the network in SVG format.
+    pub fn to_svg(&self) -> String {
+        const STEP: usize = 20;
+
+        // Comparators of one layer are drawn in several columns if their
+        // vertical lines overlap
+        let mut columns: Vec<Vec<(usize, usize)>> = Vec::new();
+        let mut gaps = Vec::new();
+        for layer in &self.layers {
+            let first = columns.len();
+            for &(i, j) in layer {
+                let free = columns[first..].iter().position(|column|
+                    column.iter().all(|&(a, b)| b < i || a > j));
+                match free {
+                    Some(k) => columns[first + k].push((i, j)),
+                    None => columns.push(vec![(i, j)]),
+                }
+            }
+            gaps.push(columns.len());
+        }
+
+        let width = (columns.len() + gaps.len() + 1) * STEP;
+        let height = (self.inputs + 1) * STEP;
+        let mut svg = String::new();
+        let _ = writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">", width, height,
+            width, height);
+        for c in 0..self.inputs {
+            let y = (c + 1) * STEP;
+            let _ = writeln!(svg, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" \
+                y2=\"{}\" stroke=\"black\"/>", STEP / 2, y, width - STEP / 2,
+                y);
+        }
+        let mut x = STEP;
+        let mut layer_end = gaps.iter().peekable();
+        for (k, column) in columns.iter().enumerate() {
+            for &(i, j) in column {
+                let (y1, y2) = ((i + 1) * STEP, (j + 1) * STEP);
+                let _ = writeln!(svg, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" \
+                    y2=\"{}\" stroke=\"black\"/>", x, y1, x, y2);
+                for y in [y1, y2].iter() {
+                    let _ = writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" \
+                        r=\"3\"/>", x, y);
+                }
+            }
+            x += STEP;
+            if layer_end.peek() == Some(&&(k + 1)) {
+                layer_end.next();
+                x += STEP;
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Returns network in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n    rankdir=LR;\n");
+        let mut last: Vec<String> = (0..self.inputs)
+            .map(|c| format!("in{}", c)).collect();
+
+        dot.push_str("    {rank=same;");
+        for node in &last {
+            let _ = write!(dot, " {};", node);
+        }
+        dot.push_str("}\n");
+        for (c, node) in last.iter().enumerate() {
+            let _ = writeln!(dot, "    {} [shape=plaintext, label=\"{}\"];",
+                node, c);
+        }
+
+        for (l, layer) in self.layers.iter().enumerate() {
+            dot.push_str("    {rank=same;");
+            for k in 0..layer.len() {
+                let _ = write!(dot, " c{}_{};", l, k);
+            }
+            dot.push_str("}\n");
+            for (k, &(i, j)) in layer.iter().enumerate() {
+                let node = format!("c{}_{}", l, k);
+                let _ = writeln!(dot, "    {} [shape=box, label=\"{}:{}\"];",
+                    node, i, j);
+                for c in [i, j].iter() {
+                    let _ = writeln!(dot, "    {} -> {} [label=\"{}\"];",
+                        last[*c], node, c);
+                    last[*c] = node.clone();
+                }
+            }
+        }
+
+        for (c, node) in last.iter().enumerate() {
+            let _ = writeln!(dot, "    out{} [shape=plaintext, label=\"{}\"];",
+                c, c);
+            let _ = writeln!(dot, "    {} -> out{} [label=\"{}\"];", node, c,
+                c);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Layer of a structured network, comparators are described by the partner
+/// of every position, so a layer can be split between threads by positions.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Layer {
+    /// Position `i` is compared with mirrored `i ^ (k - 1)` in blocks of `k`.
+    Mirror(usize),
+    /// Position `i` is compared with `i ^ j`.
+    Xor(usize),
+    /// Position `i` with `i & p == r` is compared with `i + d`.
+    Merge { p: usize, r: usize, d: usize },
+    /// Position `i` of given parity is compared with `i + 1`.
+    Pairs(usize),
+}
+
+impl Layer {
+    /// Returns position compared with `i` if it's greater than `i`.
+    #[inline]
+    pub(crate) fn partner(self, i: usize, n: usize) -> Option<usize> {
+        let j = match self {
+            Layer::Mirror(k) => i ^ (k - 1),
+            Layer::Xor(j) => i ^ j,
+            Layer::Merge { p, r, d } if i & p == r => i + d,
+            Layer::Pairs(parity) if i % 2 == parity => i + 1,
+            _ => return None,
+        };
+        if j > i && j < n {Some(j)} else {None}
+    }
+}
+
+/// Returns layers of bitonic network for `n` inputs.
+pub(crate) fn bitonic_layers(n: usize) -> Vec<Layer> {
+    let size = n.next_power_of_two();
+    let mut layers = Vec::new();
+    let mut k = 2;
+    while k <= size {
+        // First layer of a merge compares mirrored positions, so both halves
+        // can be sorted in ascending order
+        layers.push(Layer::Mirror(k));
+        let mut j = k / 4;
+        while j > 0 {
+            layers.push(Layer::Xor(j));
+            j /= 2;
+        }
+        k *= 2;
+    }
+    layers
+}
+
+/// Returns layers of Batcher's odd-even merge sort for `n` inputs,
+/// Algorithm M from Knuth's "The Art of Computer Programming", 5.2.2.
+pub(crate) fn batcher_layers(n: usize) -> Vec<Layer> {
+    let mut layers = Vec::new();
+    if n < 2 {return layers;}
+
+    let t = n.next_power_of_two().trailing_zeros();
+    let mut p = 1 << (t - 1);
+    while p > 0 {
+        let mut q = 1 << (t - 1);
+        let mut r = 0;
+        let mut d = p;
+        while d > 0 {
+            layers.push(Layer::Merge { p, r, d });
+            d = q - p;
+            q >>= 1;
+            r = p;
+        }
+        p >>= 1;
+    }
+    layers
+}
+
+/// Calls `f` for every comparator of the layers in order of execution.
+pub(crate) fn layer_comparators<F: FnMut(usize, usize)>(n: usize,
+layers: &[Layer], mut f: F) {
+    for layer in layers {
+        for i in 0..n {
+            if let Some(j) = layer.partner(i, n) {f(i, j);}
+        }
+    }
+}
+
+/// Calls `f` for every comparator of bitonic network for `n` inputs.
+pub(crate) fn bitonic_comparators<F: FnMut(usize, usize)>(n: usize, f: F) {
+    layer_comparators(n, &bitonic_layers(n), f);
+}
+
+/// Calls `f` for every comparator of Batcher's odd-even merge sort for `n`
+/// inputs.
+pub(crate) fn batcher_comparators<F: FnMut(usize, usize)>(n: usize, f: F) {
+    layer_comparators(n, &batcher_layers(n), f);
+}
+
+/// Number of threads used by parallel sorts by default.
+pub(crate) fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Pointer to the elements of a slice shared by threads, which access
+/// distinct elements.
+struct SharedSlice<T>(*mut T);
+
+unsafe impl<T: Send> Sync for SharedSlice<T> {}
+
+/// Barrier, which releases all threads for good once it's poisoned, so
+/// threads don't wait forever for one, which panicked.
+struct Barrier {
+    /// Number of waiting threads, number of the current round and whether
+    /// the barrier is poisoned
+    state: Mutex<(usize, usize, bool)>,
+    released: Condvar,
+    threads: usize,
+}
+
+impl Barrier {
+    fn new(threads: usize) -> Self {
+        Self {state: Mutex::new((0, 0, false)), released: Condvar::new(),
+            threads}
+    }
+
+    /// Blocks until all threads call `wait`, returns `false` if the barrier
+    /// is poisoned.
+    fn wait(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let round = state.1;
+        state.0 += 1;
+        if state.0 == self.threads {
+            *state = (0, round + 1, state.2);
+            self.released.notify_all();
+        }
+        while state.1 == round && !state.2 {
+            state = self.released.wait(state).unwrap();
+        }
+        !state.2
+    }
+
+    fn poison(&self) {
+        self.state.lock().unwrap().2 = true;
+        self.released.notify_all();
+    }
+}
+
+/// Poisons the barrier if the thread unwinds.
+struct PoisonOnPanic<'a>(&'a Barrier);
+
+impl Drop for PoisonOnPanic<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {self.0.poison();}
+    }
+}
+
+/// Executes layers splitting positions between `threads` scoped threads,
+/// which wait for each other after every layer. If `repeat` is set, layers
+/// are executed again until they don't swap anything. If a comparison
+/// panics, other threads stop at the next layer and the panic is propagated.
+pub(crate) fn par_apply_layers<T: PartialOrd + Send>(input: &mut [T],
+layers: &[Layer], threads: usize, repeat: bool) {
+    let n = input.len();
+    if n < 2 || layers.is_empty() {return;}
+
+    let chunk = n.div_ceil(threads.max(1));
+    let threads = n.div_ceil(chunk);
+    let shared = SharedSlice(input.as_mut_ptr());
+    let barrier = Barrier::new(threads);
+    // Number of the last pass, which swapped something, plus one, so the
+    // flag never has to be reset
+    let last_swap = std::sync::atomic::AtomicUsize::new(0);
+
+    let worker = |start: usize| {
+        let _guard = PoisonOnPanic(&barrier);
+        let end = std::cmp::min(start + chunk, n);
+        for pass in 1.. {
+            let mut swapped = false;
+            for layer in layers {
+                for i in start..end {
+                    if let Some(j) = layer.partner(i, n) {
+                        // Comparators of a layer touch distinct positions
+                        // and positions are split between threads by the
+                        // smaller one, so no element is used twice
+                        unsafe {
+                            let a = &mut *shared.0.add(i);
+                            let b = &mut *shared.0.add(j);
+                            if *a > *b {
+                                std::mem::swap(a, b);
+                                swapped = true;
+                            }
+                        }
+                    }
+                }
+                if swapped {
+                    last_swap.fetch_max(pass, Ordering::Relaxed);
+                }
+                if !barrier.wait() {return;}
+            }
+            if !repeat || last_swap.load(Ordering::Relaxed) < pass {break;}
+        }
+    };
+
+    std::thread::scope(|scope| {
+        for t in 1..threads {
+            let worker = &worker;
+            scope.spawn(move || worker(t * chunk));
+        }
+        worker(0);
+    });
+}
+
+/// Calls `f` for every comparator of Parberry's pairwise sorting network for
+/// `n` inputs.
+pub(crate) fn pairwise_comparators<F: FnMut(usize, usize)>(n: usize,
+mut f: F) {
+    // Sorting pairs, pairs of pairs and so on by their first elements
+    let mut a = 1;
+    while a < n {
+        let mut b = a;
+        let mut c = 0;
+        while b < n {
+            f(b - a, b);
+            b += 1;
+            c = (c + 1) % a;
+            if c == 0 {b += a;}
+        }
+        a *= 2;
+    }
+
+    // Merging the sorted pairs
+    a /= 4;
+    let mut e = 1;
+    while a > 0 {
+        let mut d = e;
+        while d > 0 {
+            let mut b = (d + 1) * a;
+            let mut c = 0;
+            while b < n {
+                f(b - d * a, b);
+                b += 1;
+                c = (c + 1) % a;
+                if c == 0 {b += a;}
+            }
+            d /= 2;
+        }
+        a /= 2;
+        e = e * 2 + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_builders_sort() {
+        for n in 0..=20 {
+            debug_assert!(Network::bitonic(n).is_sorting(), "bitonic {}", n);
+            debug_assert!(Network::batcher(n).is_sorting(), "batcher {}", n);
+            debug_assert!(Network::pairwise(n).is_sorting(), "pairwise {}",
+                n);
+            debug_assert!(Network::insertion(n).is_sorting(),
+                "insertion {}", n);
+        }
+        for n in 0..=16 {
+            debug_assert!(Network::optimal(n).unwrap().is_sorting());
+        }
+        debug_assert!(Network::optimal(17).is_none());
+    }
+    #[test]
+    fn test_sizes_and_depths() {
+        // Sizes and depths for 16 inputs from Knuth's tables
+        let bitonic = Network::bitonic(16);
+        debug_assert_eq!((bitonic.size(), bitonic.depth()), (80, 10));
+        let batcher = Network::batcher(16);
+        debug_assert_eq!((batcher.size(), batcher.depth()), (63, 10));
+        let pairwise = Network::pairwise(16);
+        debug_assert_eq!((pairwise.size(), pairwise.depth()), (63, 10));
+        let insertion = Network::insertion(8);
+        debug_assert_eq!((insertion.size(), insertion.depth()), (28, 13));
+        let optimal = Network::optimal(16).unwrap();
+        debug_assert_eq!((optimal.size(), optimal.depth()), (60, 10));
+    }
+    #[test]
+    fn test_not_sorting() {
+        debug_assert!(!Network::from_comparators(3, &[(0, 1), (1, 2)])
+            .is_sorting());
+        debug_assert!(!Network::new(2).is_sorting());
+        debug_assert!(Network::new(1).is_sorting());
+    }
+    #[test]
+    fn test_layers() {
+        let network = Network::from_comparators(4,
+            &[(0, 1), (2, 3), (0, 2), (1, 3), (1, 2)]);
+        debug_assert_eq!(network.layers(),
+            &[vec![(0, 1), (2, 3)], vec![(0, 2), (1, 3)], vec![(1, 2)]]);
+        debug_assert_eq!(network.comparators().count(), 5);
+        debug_assert_eq!(network.inputs(), 4);
+    }
+    #[test]
+    #[should_panic]
+    fn test_invalid_comparator() {
+        Network::new(4).push(2, 1);
+    }
+    #[test]
+    fn test_apply() {
+        let network = Network::pairwise(10);
+        let mut vector_in = vec![10, 20, 11, 24, 13, 30, 7, 1, 5, 5];
+        network.apply(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 5, 5, 7, 10, 11, 13, 20, 24, 30]);
+        let mut strings = vec!["rustc", "cargo", "rustup"];
+        Network::bitonic(3).apply(&mut strings);
+        debug_assert_eq!(strings, vec!["cargo", "rustc", "rustup"]);
+    }
+    #[test]
+    #[should_panic]
+    fn test_apply_wrong_length() {
+        Network::batcher(4).apply(&mut [3, 2, 1]);
+    }
+    #[test]
+    fn test_svg() {
+        let svg = Network::optimal(4).unwrap().to_svg();
+        debug_assert!(svg.starts_with("<svg"));
+        debug_assert!(svg.ends_with("</svg>\n"));
+        debug_assert_eq!(svg.matches("<circle").count(), 10);
+        // 4 positions and 5 comparators
+        debug_assert_eq!(svg.matches("<line").count(), 9);
+    }
+    #[test]
+    fn test_par_apply_layers_panic() {
+        let sorts: [fn(&mut [Panicky], usize); 3] = [
+            crate::par_bitonic_sort_with, crate::par_oddeven_sort_with,
+            crate::par_batcher_sort_with,
+        ];
+        for sort in sorts {
+            let comparisons = Arc::new(AtomicUsize::new(0));
+            let mut items: Vec<Panicky> = (0..1000).rev()
+                .map(|x| Panicky(x, comparisons.clone())).collect();
+            // Other threads mustn't wait at the barrier for the panicked one
+            let result = std::panic::catch_unwind(
+                std::panic::AssertUnwindSafe(|| sort(&mut items, 4)));
+            debug_assert!(result.is_err());
+            let mut values: Vec<usize> = items.iter().map(|x| x.0).collect();
+            values.sort();
+            debug_assert_eq!(values, (0..1000).collect::<Vec<usize>>());
+        }
+    }
+    #[test]
+    fn test_dot() {
+        let dot = Network::from_comparators(2, &[(0, 1)]).to_dot();
+        debug_assert!(dot.contains("in0 -> c0_0"));
+        debug_assert!(dot.contains("in1 -> c0_0"));
+        debug_assert!(dot.contains("c0_0 -> out1"));
+        debug_assert!(dot.ends_with("}\n"));
+    }
+
+    /// Panics on the 500th comparison.
+    struct Panicky(usize, Arc<AtomicUsize>);
+
+    impl PartialEq for Panicky {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+
+    impl PartialOrd for Panicky {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            if self.1.fetch_add(1, Ordering::Relaxed) == 500 {
+                panic!("Comparison failed");
+            }
+            self.0.partial_cmp(&other.0)
+        }
+    }
+}
@@ -0,0 +1,207 @@
+/// Sorts a fixed-size array in-place using optimal
+/// [Sorting network](https://en.wikipedia.org/wiki/Sorting_network).
+/// All kinds of arrays of up to 16 elements can be sorted as long as they
+/// implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+///
+/// Sorting network is a fixed sequence of comparators, each of them compares
+/// two elements and swaps them if they are out of order, like
+/// `compare_and_swap` of bitonic sort. The sequence doesn't depend on the
+/// data, so there are no unpredictable branches, and comparators of one layer
+/// are independent of each other. This makes networks the fastest way to
+/// sort tiny arrays, which is why they are the core of the base case of
+/// quicksorts, `ksort` and `intro_sort`, see `branchless_insertion_sort`.
+/// Comparators can swap equal elements, so networks aren't stable, and the
+/// base case of `merge_sort` uses the other branchless kernels without them.
+///
+/// Networks have the smallest known number of comparators for every size, it
+/// is proven optimal up to 12 elements. Their depth is optimal for up to 9
+/// and for 11 elements, other sizes are one layer deeper than the optimum.
+/// Comparators don't branch: both elements are read, and written back in
+/// the order selected by comparison, so for primitive types the compiler
+/// emits conditional moves.
+///
+/// # Examples
+/// ```rust
+/// let mut array = [5, 3, 2, 4];
+/// sorting_rs::network_sort(&mut array);
+/// assert_eq!(array, [2, 3, 4, 5]);
+/// ```
+/// ```rust
+/// let mut strings = ["rustc", "cargo", "rustup"];
+/// sorting_rs::network_sort(&mut strings);
+/// assert_eq!(strings, ["cargo", "rustc", "rustup"]);
+/// ```
+/// Arrays of more than 16 elements are rejected at compile time:
+/// ```compile_fail
+/// sorting_rs::network_sort(&mut [0; 17]);
+/// ```
+pub fn network_sort<T: PartialOrd, const N: usize>(input: &mut [T; N]) {
+    const {assert!(N <= NETWORK_LEN, "Network can sort at most 16 elements")};
+    apply_network(input);
+}
+
+/// Largest slice sorted by a network.
+pub(crate) const NETWORK_LEN: usize = 16;
+
+/// Puts the smaller of elements at `i < j` first without branching.
+#[inline(always)]
+pub(crate) fn compare_and_swap<T: PartialOrd>(input: &mut [T], i: usize,
+j: usize) {
+    let (left, right) = input.split_at_mut(j);
+    let (a, b) = (&mut left[i], &mut right[0]);
+    let swap = *b < *a;
+    // SAFETY: `a` and `b` are distinct valid references, and nothing can
+    // panic between reading the values and writing them back, so every value
+    // stays owned exactly once
+    unsafe {
+        let x = std::ptr::read(a);
+        let y = std::ptr::read(b);
+        let (min, max) = if swap {(y, x)} else {(x, y)};
+        std::ptr::write(a, min);
+        std::ptr::write(b, max);
+    }
+}
+
+/// Generates `apply_network`, which runs the network for the length of the
+/// slice. Comparators are unrolled, so for arrays the match and all bounds
+/// checks are resolved at compile time. Comparator lists are also kept in
+/// `NETWORKS` for `Network::optimal`.
+macro_rules! networks {
+    ($($n: literal => [$(($i: literal, $j: literal)), *],)*) => {
+        #[inline(always)]
+        pub(crate) fn apply_network<T: PartialOrd>(input: &mut [T]) {
+            match input.len() {
+                $($n => {$(compare_and_swap(input, $i, $j);)*},)*
+                _ => {},
+            }
+        }
+
+        /// Comparators of the networks for every number of inputs, listed
+        /// layer by layer.
+        pub(crate) const NETWORKS: [&[(usize, usize)]; NETWORK_LEN + 1] = [
+            &[], &[], $(&[$(($i, $j)), *],)*
+        ];
+    }
+}
+
+networks! {
+    // 2 inputs, 1 comparator, depth 1
+    2 => [(0, 1)],
+    // 3 inputs, 3 comparators, depth 3
+    3 => [(0, 2), (0, 1), (1, 2)],
+    // 4 inputs, 5 comparators, depth 3
+    4 => [(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    // 5 inputs, 9 comparators, depth 5
+    5 => [(0, 3), (1, 4), (0, 2), (1, 3), (0, 1), (2, 4), (1, 2), (3, 4),
+        (2, 3)],
+    // 6 inputs, 12 comparators, depth 5
+    6 => [(0, 5), (1, 3), (2, 4), (1, 2), (3, 4), (0, 3), (2, 5), (0, 1),
+        (2, 3), (4, 5), (1, 2), (3, 4)],
+    // 7 inputs, 16 comparators, depth 6
+    7 => [(0, 6), (2, 3), (4, 5), (0, 2), (1, 4), (3, 6), (0, 1), (2, 5),
+        (3, 4), (1, 2), (4, 6), (2, 3), (4, 5), (1, 2), (3, 4), (5, 6)],
+    // 8 inputs, 19 comparators, depth 6
+    8 => [(0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7),
+        (0, 1), (2, 3), (4, 5), (6, 7), (2, 4), (3, 5), (1, 4), (3, 6), (1, 2),
+        (3, 4), (5, 6)],
+    // 9 inputs, 25 comparators, depth 7
+    9 => [(0, 3), (1, 7), (2, 5), (4, 8), (0, 7), (2, 4), (3, 8), (5, 6),
+        (0, 2), (1, 3), (4, 5), (7, 8), (1, 4), (3, 6), (5, 7), (0, 1), (2, 4),
+        (3, 5), (6, 8), (2, 3), (4, 5), (6, 7), (1, 2), (3, 4), (5, 6)],
+    // 10 inputs, 29 comparators, depth 8
+    10 => [(0, 8), (1, 9), (2, 7), (3, 5), (4, 6), (0, 2), (1, 4), (5, 8),
+        (7, 9), (0, 3), (2, 4), (5, 7), (6, 9), (0, 1), (3, 6), (8, 9), (1, 5),
+        (2, 3), (4, 8), (6, 7), (1, 2), (3, 5), (4, 6), (7, 8), (2, 3), (4, 5),
+        (6, 7), (3, 4), (5, 6)],
+    // 11 inputs, 35 comparators, depth 8
+    11 => [(0, 9), (1, 6), (2, 4), (3, 7), (5, 8), (0, 1), (3, 5), (4, 10),
+        (6, 9), (7, 8), (1, 3), (2, 5), (4, 7), (8, 10), (0, 4), (1, 2), (3, 7),
+        (5, 9), (6, 8), (0, 1), (2, 6), (4, 5), (7, 8), (9, 10), (2, 4), (3, 6),
+        (5, 7), (8, 9), (1, 2), (3, 4), (5, 6), (7, 8), (2, 3), (4, 5), (6, 7)],
+    // 12 inputs, 39 comparators, depth 9
+    12 => [(0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9), (0, 1), (2, 5),
+        (3, 4), (6, 9), (7, 8), (10, 11), (0, 2), (1, 6), (5, 10), (9, 11),
+        (0, 3), (1, 2), (4, 6), (5, 7), (8, 11), (9, 10), (1, 4), (3, 5),
+        (6, 8), (7, 10), (1, 3), (2, 5), (6, 9), (8, 10), (2, 3), (4, 5),
+        (6, 7), (8, 9), (4, 6), (5, 7), (3, 4), (5, 6), (7, 8)],
+    // 13 inputs, 45 comparators, depth 10
+    13 => [(0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8), (1, 6), (2, 3),
+        (4, 11), (7, 9), (8, 10), (0, 4), (1, 2), (3, 6), (7, 8), (9, 10),
+        (11, 12), (4, 6), (5, 9), (8, 11), (10, 12), (0, 5), (3, 8), (4, 7),
+        (6, 11), (9, 10), (0, 1), (2, 5), (6, 9), (7, 8), (10, 11), (1, 3),
+        (2, 4), (5, 6), (9, 10), (1, 2), (3, 4), (5, 7), (6, 8), (2, 3), (4, 5),
+        (6, 7), (8, 9), (3, 4), (5, 6)],
+    // 14 inputs, 51 comparators, depth 10
+    14 => [(0, 13), (1, 12), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7),
+        (2, 9), (3, 4), (6, 13), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8),
+        (7, 9), (10, 11), (12, 13), (0, 2), (1, 3), (4, 10), (5, 11), (6, 7),
+        (8, 9), (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (1, 4),
+        (2, 6), (5, 8), (7, 10), (9, 13), (2, 4), (3, 6), (9, 12), (11, 13),
+        (3, 5), (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8), (9, 10),
+        (11, 12), (6, 7), (8, 9)],
+    // 15 inputs, 56 comparators, depth 10
+    15 => [(0, 13), (1, 12), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5),
+        (1, 7), (2, 9), (3, 4), (6, 13), (8, 14), (11, 12), (0, 1), (2, 3),
+        (4, 5), (6, 8), (7, 9), (10, 11), (12, 13), (0, 2), (1, 3), (4, 10),
+        (5, 11), (6, 7), (8, 9), (12, 14), (1, 2), (3, 12), (4, 6), (5, 7),
+        (8, 10), (9, 11), (13, 14), (1, 4), (2, 6), (5, 8), (7, 10), (9, 13),
+        (11, 14), (2, 4), (3, 6), (9, 12), (11, 13), (3, 5), (6, 8), (7, 9),
+        (10, 12), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9)],
+    // 16 inputs, 60 comparators, depth 10
+    16 => [(0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10),
+        (0, 5), (1, 7), (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12),
+        (0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11), (12, 13), (14, 15),
+        (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 14), (13, 15),
+        (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14), (1, 4),
+        (2, 6), (5, 8), (7, 10), (9, 13), (11, 14), (2, 4), (3, 6), (9, 12),
+        (11, 13), (3, 5), (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8),
+        (9, 10), (11, 12), (6, 7), (8, 9)],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network() {
+        let mut array = [10, 20, 11, 24, 13, 30, 7, 1];
+        network_sort(&mut array);
+        debug_assert_eq!(array, [1, 7, 10, 11, 13, 20, 24, 30]);
+    }
+    #[test]
+    fn test_network_empty() {
+        let mut array: [i32; 0] = [];
+        network_sort(&mut array);
+        debug_assert_eq!(array, []);
+    }
+    #[test]
+    fn test_network_len1() {
+        let mut array = [1];
+        network_sort(&mut array);
+        debug_assert_eq!(array, [1]);
+    }
+    #[test]
+    fn test_networks_zero_one() {
+        // By 0-1 principle a network sorts everything if it sorts all
+        // sequences of zeros and ones
+        let sizes = [0, 0, 1, 3, 5, 9, 12, 16, 19, 25, 29, 35, 39, 45, 51, 56,
+            60];
+        for (n, network) in NETWORKS.iter().enumerate() {
+            debug_assert_eq!(network.len(), sizes[n]);
+            for bits in 0u32..1 << n {
+                let mut input: Vec<u32> = (0..n).map(|i| (bits >> i) & 1)
+                    .collect();
+                apply_network(&mut input);
+                debug_assert!(input.windows(2).all(|w| w[0] <= w[1]));
+            }
+        }
+    }
+    #[test]
+    fn test_network_strings() {
+        let mut array = ["f", "e", "d", "c", "b", "a", "g", "h", "i", "j", "k",
+            "l", "m", "n", "o", "p"].map(String::from);
+        network_sort(&mut array);
+        debug_assert!(array.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -1,11 +1,22 @@
 /// Sorts a slice in-place using
-/// [N-heap sort](https://en.wikipedia.org/wiki/Heapsort)
-/// 
+/// [N-heap sort](https://en.wikipedia.org/wiki/D-ary_heap)
+///
 /// All kinds of slices can be sorted as long as they implement
 /// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
 ///
-/// 3 children are a bit more effective than 2, though 4 and more are generally
-/// less effective, but you can modify this parameter by editing souces.
+/// `dary_heap_sort::<D, _>` uses heap where every node has `D` children. Wider
+/// heap is lower, so less elements are moved when sifting down, but each level
+/// needs more comparisons to find the largest child. 3 children are a bit more
+/// effective than 2, `nheap_sort` is the `D = 3` version. With 4 or 8 children
+/// all the children of a node often share one cache line, which can make them
+/// faster on some machines.
+///
+/// For `D = 4` and `D = 8` bottom-up sift is used: path of the largest
+/// children is followed down to a leaf without comparing them to the sifted
+/// element, then the place of the element is searched on the way back up.
+/// The largest child is found by a tournament, so comparisons of each level
+/// don't depend on each other. This saves one comparison per level, as sifted
+/// elements usually belong close to the leaves.
 ///
 /// # Examples
 /// ```rust
@@ -18,34 +29,151 @@
 /// sorting_rs::nheap_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// let mut vec = vec![5,3,2,4,8,1,9,7,6];
+/// sorting_rs::dary_heap_sort::<4, _>(&mut vec);
+/// assert_eq!(vec, &[1,2,3,4,5,6,7,8,9]);
+/// ```
+/// Heaps with less than 2 children are rejected at compile time:
+/// ```compile_fail
+/// sorting_rs::dary_heap_sort::<1, _>(&mut [2, 1]);
+/// ```
 pub fn nheap_sort<T: PartialOrd>(input: &mut [T]) {
+    dary_heap_sort::<3, _>(input);
+}
+
+pub fn dary_heap_sort<const D: usize, T: PartialOrd>(input: &mut [T]) {
+    const {assert!(D >= 2, "Heap must have at least 2 children")};
+    if input.len() < 2 {return;}
+
+    dary_heapify::<D, _>(input);
+    dary_sort_heap::<D, _>(input);
+}
+
+/// Max-priority queue based on
+/// [d-ary heap](https://en.wikipedia.org/wiki/D-ary_heap), where every node
+/// has `D` children.
+///
+/// Insertion needs `log`<sub>`D`</sub>`n` comparisons, removal of the largest
+/// element `Dlog`<sub>`D`</sub>`n`, so wider heaps suit workloads where
+/// insertions are more frequent.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::DaryHeap;
+///
+/// let mut heap: DaryHeap<_, 4> = DaryHeap::from_vec(vec![5, 2, 7]);
+/// heap.push(9);
+/// assert_eq!(heap.peek(), Some(&9));
+/// assert_eq!(heap.pop(), Some(9));
+/// assert_eq!(heap.into_sorted_vec(), &[2, 5, 7]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DaryHeap<T, const D: usize> {
+    data: Vec<T>,
+}
+
+impl<T: PartialOrd, const D: usize> DaryHeap<T, D> {
+    pub fn new() -> Self {
+        const {assert!(D >= 2, "Heap must have at least 2 children")};
+        Self { data: Vec::new() }
+    }
+
+    /// Builds heap from a vector in linear time.
+    pub fn from_vec(mut data: Vec<T>) -> Self {
+        const {assert!(D >= 2, "Heap must have at least 2 children")};
+        dary_heapify::<D, _>(&mut data);
+        Self { data }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+
+        let mut i = self.data.len() - 1;
+        while i > 0 {
+            let parent = (i - 1) / D;
+            if self.data[parent] < self.data[i] {
+                self.data.swap(parent, i);
+                i = parent;
+            } else {break;}
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let n = self.data.len();
+        if n == 0 {return None;}
+
+        self.data.swap(0, n - 1);
+        let item = self.data.pop();
+        if n > 2 {dary_sift::<D, _>(&mut self.data, 0, n - 2);}
+        item
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns elements in heap order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns elements sorted in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        dary_sort_heap::<D, _>(&mut self.data);
+        self.data
+    }
+}
+
+impl<T: PartialOrd, const D: usize> Default for DaryHeap<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn dary_heapify<const D: usize, T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
-    let children = 3;
-    for i in (0..=input.len()).rev() {
-        nheap_sift(input, children, i, input.len() - 1);
+    let end = input.len() - 1;
+    for i in (0..=(end - 1) / D).rev() {
+        dary_sift::<D, T>(input, i, end);
     }
+}
 
+fn dary_sort_heap<const D: usize, T: PartialOrd>(input: &mut [T]) {
     for i in (1..input.len()).rev() {
         input.swap(i, 0);
-        nheap_sift(input, children, 0, i - 1);
+        dary_sift::<D, T>(input, 0, i - 1);
     }
 }
 
-fn nheap_sift<T: PartialOrd>(input: &mut [T], children: usize, start: usize,
+fn dary_sift<const D: usize, T: PartialOrd>(input: &mut [T], start: usize,
 end: usize) {
+    if D == 4 || D == 8 {
+        dary_sift_bottom_up::<D, T>(input, start, end);
+    } else {
+        dary_sift_down::<D, T>(input, start, end);
+    }
+}
+
+fn dary_sift_down<const D: usize, T: PartialOrd>(input: &mut [T],
+start: usize, end: usize) {
     let mut root = start;
-    
+
     loop {
-        let child = root * children + 1;
+        let child = root * D + 1;
         if child > end {break;}
         let mut max = child;
 
-        for k in 2..children + 1 {
-            let current = root * children + k;
-            if current > end {break;}
-
+        for current in child + 1..std::cmp::min(child + D, end + 1) {
             if input[current] > input[max] {
                 max = current;
             }
@@ -57,9 +185,82 @@ end: usize) {
     }
 }
 
+fn dary_sift_bottom_up<const D: usize, T: PartialOrd>(input: &mut [T],
+start: usize, end: usize) {
+    let mut current = start;
+
+    loop {
+        let child = current * D + 1;
+        if child > end {break;}
+        current = if child + D - 1 <= end {
+            max_child_tournament(input, child, D)
+        } else {
+            max_child_linear(input, child, end)
+        };
+    }
+
+    while input[start] > input[current] {
+        current = (current - 1) / D;
+    }
+    input.swap(current, start);
+
+    while current > start {
+        current = (current - 1) / D;
+        input.swap(current, start);
+    }
+}
+
+fn max_child_tournament<T: PartialOrd>(input: &[T], first: usize, n: usize)
+-> usize {
+    if n == 1 {return first;}
+    let left = max_child_tournament(input, first, n / 2);
+    let right = max_child_tournament(input, first + n / 2, n / 2);
+    if input[right] > input[left] {right} else {left}
+}
+
+fn max_child_linear<T: PartialOrd>(input: &[T], first: usize, last: usize)
+-> usize {
+    let mut max = first;
+    for current in first + 1..=last {
+        if input[current] > input[max] {
+            max = current;
+        }
+    }
+    max
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+    use std::cmp::Ordering;
+
+    struct Counted<'a>(i32, &'a Cell<usize>);
+
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+    impl PartialOrd for Counted<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.1.set(self.1.get() + 1);
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    fn count_comparisons<F: Fn(&mut [Counted], usize, usize)>(sift: F)
+    -> usize {
+        let counter = Cell::new(0);
+        let mut input: Vec<Counted> = (0..5000)
+            .map(|x| Counted((x * 367) % 5000, &counter)).collect();
+        let end = input.len() - 1;
+        for i in (0..=(end - 1) / 4).rev() {sift(&mut input, i, end);}
+        for i in (1..input.len()).rev() {
+            input.swap(i, 0);
+            sift(&mut input, 0, i - 1);
+        }
+        debug_assert!(input.windows(2).all(|w| w[0].0 <= w[1].0));
+        counter.get()
+    }
 
     #[test]
     fn test_heap() {
@@ -85,4 +286,63 @@ mod tests {
         nheap_sort(&mut vector_in);
         debug_assert_eq!(vector_in, vec![1]);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_dary_heap() {
+        let reference: Vec<i32> = (0..1000).collect();
+        let input: Vec<i32> = (0..1000).map(|x| (x * 367) % 1000).collect();
+        for len in [0, 1, 2, 5, 9, 17, 100, 1000].iter() {
+            let mut expected = input[..*len].to_vec();
+            expected.sort();
+            macro_rules! check {($($d: expr), *) => {$(
+                let mut vector_in = input[..*len].to_vec();
+                dary_heap_sort::<$d, _>(&mut vector_in);
+                debug_assert_eq!(vector_in, expected);
+            )*}}
+            check!(2, 3, 4, 5, 8, 16);
+        }
+        let mut vector_in = input.clone();
+        dary_heap_sort::<8, _>(&mut vector_in);
+        debug_assert_eq!(vector_in, reference);
+    }
+    #[test]
+    fn test_dary_bottom_up_comparisons() {
+        let down = count_comparisons(|input, start, end|
+            dary_sift_down::<4, _>(input, start, end));
+        let bottom_up = count_comparisons(|input, start, end|
+            dary_sift_bottom_up::<4, _>(input, start, end));
+        debug_assert!(bottom_up < down);
+    }
+    #[test]
+    fn test_dary_heap_queue() {
+        let mut heap: DaryHeap<i32, 4> = DaryHeap::new();
+        let mut reference = vec![];
+        for i in 0..500 {
+            let item = (i * 367) % 113;
+            heap.push(item);
+            reference.push(item);
+            if i % 3 == 0 {
+                reference.sort();
+                debug_assert_eq!(heap.peek(), reference.last());
+                debug_assert_eq!(heap.pop(), reference.pop());
+            }
+        }
+        debug_assert_eq!(heap.len(), reference.len());
+        reference.sort();
+        debug_assert_eq!(heap.clone().into_sorted_vec(), reference);
+        while let Some(item) = heap.pop() {
+            debug_assert_eq!(Some(item), reference.pop());
+        }
+        debug_assert!(heap.is_empty());
+    }
+    #[test]
+    fn test_dary_heap_queue_from_vec() {
+        let input: Vec<i32> = (0..100).map(|x| (x * 37) % 101).collect();
+        let mut reference = input.clone();
+        reference.sort();
+        let heap: DaryHeap<i32, 3> = DaryHeap::from_vec(input.clone());
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+        let mut heap: DaryHeap<i32, 8> = DaryHeap::from_vec(input);
+        debug_assert_eq!(heap.pop(), reference.pop());
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+    }
+}
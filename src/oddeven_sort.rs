@@ -1,3 +1,6 @@
+use crate::network::Layer;
+use crate::network_sort::compare_and_swap;
+
 /// Sorts a slice in-place using
 /// [Odd-even sort](https://en.wikipedia.org/wiki/Odd-even_sort)
 /// Sorts a slice in-place using
@@ -9,7 +12,16 @@
 /// It's a relatively simple algorithm developed originally for use
 /// on parallel processors with local interconnections.
 /// 
-/// Batcher algorithm is the enchanced version of odd-even algorithm.
+/// Batcher algorithm is the enchanced version of odd-even algorithm. It
+/// executes comparators of Batcher's merge exchange network, which works for
+/// any length without padding, its layers can be inspected with
+/// [`Network::batcher`](crate::network::Network::batcher).
+///
+/// `par_oddeven_sort` and `par_batcher_sort` run the comparators on scoped
+/// threads, one per available core, `_with` versions take the number of
+/// threads. Every thread executes comparators of its part of the slice and
+/// waits for the others before the next phase. Odd-even sort still needs up
+/// to `n` phases, so it pays off only when comparisons are expensive.
 ///
 /// # Examples
 /// ```rust
@@ -32,7 +44,11 @@
 /// sorting_rs::oddeven_batcher_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// let mut vec: Vec<u32> = (0..1000).rev().collect();
+/// sorting_rs::par_batcher_sort_with(&mut vec, 4);
+/// assert_eq!(vec, (0..1000).collect::<Vec<u32>>());
+/// ```
 pub fn oddeven_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
     
@@ -64,34 +80,29 @@ pub fn oddeven_sort<T: PartialOrd>(input: &mut [T]) {
 pub fn oddeven_batcher_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
-    oddeven_batcher_sort_ranges(input, 0, input.len() - 1);
+    crate::network::batcher_comparators(input.len(), |i, j|
+        compare_and_swap(input, i, j));
 }
 
-fn oddeven_batcher_sort_ranges<T: PartialOrd>(input: &mut [T], start:usize,
-end:usize) {
-    if end >= start {
-        let mid = start + (end - start) / 2;
-        oddeven_batcher_sort_ranges(input, end, mid);
-        oddeven_batcher_sort_ranges(input, mid + 1, start);
-        oddeven_batcher_merge(input, start, end, 1);
-    } 
+pub fn par_oddeven_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_oddeven_sort_with(input, crate::network::default_threads());
 }
 
-fn oddeven_batcher_merge<T: PartialOrd>(input: &mut [T], start:usize, end:usize,
-r: usize) {
-    let step = r * 2;
-    
-    if step < end - start {
-        oddeven_batcher_merge(input, start, end, step);
-        oddeven_batcher_merge(input, start + r, end, step);
+pub fn par_oddeven_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    // Odd phase goes first, like in the sequential version
+    let phases = [Layer::Pairs(1), Layer::Pairs(0)];
+    crate::network::par_apply_layers(input, &phases, threads, true);
+}
 
-        for i in start + r..=end - r {
-            if input[i] > input[i + r] {input.swap(i, i + r);}
-        }
-    }
-    else {
-        if input[start] > input[start + r] {input.swap(start, start + r);}
-    }
+pub fn par_batcher_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_batcher_sort_with(input, crate::network::default_threads());
+}
+
+pub fn par_batcher_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    let layers = crate::network::batcher_layers(input.len());
+    crate::network::par_apply_layers(input, &layers, threads, false);
 }
 
 #[cfg(test)]
@@ -123,6 +134,39 @@ mod tests {
         debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
     }
     #[test]
+    fn test_oddeven_batcher_lengths() {
+        for len in 0..100 {
+            let mut vector_in: Vec<i32> = (0..len).map(|x| (x * 37) % 11)
+                .collect();
+            let mut expected = vector_in.clone();
+            expected.sort();
+            oddeven_batcher_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
+    fn test_par_oddeven_and_batcher() {
+        for len in [0, 1, 2, 5, 17, 100, 1000].iter() {
+            let input: Vec<i32> = (0..*len).map(|x| (x * 367) % 101).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            for threads in [1, 3, 8].iter() {
+                let mut vector_in = input.clone();
+                par_oddeven_sort_with(&mut vector_in, *threads);
+                debug_assert_eq!(vector_in, expected);
+                let mut vector_in = input.clone();
+                par_batcher_sort_with(&mut vector_in, *threads);
+                debug_assert_eq!(vector_in, expected);
+            }
+            let mut vector_in = input.clone();
+            par_oddeven_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+            let mut vector_in = input.clone();
+            par_batcher_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
     fn test_oddeven_batcher_empty() {
         let mut vector_in:Vec<i32> = vec![];
         oddeven_batcher_sort(&mut vector_in);
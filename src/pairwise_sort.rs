@@ -0,0 +1,97 @@
+use crate::network_sort::compare_and_swap;
+
+/// Sorts a slice in-place using
+/// [Pairwise sorting network](https://en.wikipedia.org/wiki/Pairwise_sorting_network)
+///
+/// All kinds of slices can be sorted as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+///
+/// Network was presented by Ian Parberry in 1992. Elements are first sorted
+/// in pairs, then pairs are sorted by their first elements, pairs of pairs
+/// by their first pairs and so on, after which the pairs are merged. It has
+/// the same number of comparators and depth as Batcher's odd-even merge
+/// sort, but the comparators have simpler structure.
+///
+/// Any length is sorted without padding: comparators touching positions
+/// above the length are skipped. Comparators are grouped into layers of
+/// independent ones by
+/// [`Network::pairwise`](crate::network::Network::pairwise), comparators of
+/// each layer can be executed in parallel.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5,3,2,4];
+/// sorting_rs::pairwise_sort(&mut vec);
+/// assert_eq!(vec, &[2,3,4,5]);
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// sorting_rs::pairwise_sort(&mut strings);
+/// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
+/// ```
+/// ```rust
+/// use sorting_rs::network::Network;
+///
+/// let schedule = Network::pairwise(6);
+/// assert_eq!(schedule.depth(), 6);
+/// for layer in schedule.layers() {
+///     // Comparators of a layer touch distinct positions
+///     assert!(layer.len() <= 3);
+/// }
+/// ```
+pub fn pairwise_sort<T: PartialOrd>(input: &mut [T]) {
+    if input.len() < 2 {return;}
+
+    crate::network::pairwise_comparators(input.len(), |i, j|
+        compare_and_swap(input, i, j));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairwise() {
+        let mut vector_in = vec![10, 20, 11, 24];
+        pairwise_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
+    }
+    #[test]
+    fn test_pairwise_lengths() {
+        for len in 0..100 {
+            let mut vector_in: Vec<i32> = (0..len).map(|x| (x * 37) % 11)
+                .collect();
+            let mut expected = vector_in.clone();
+            expected.sort();
+            pairwise_sort(&mut vector_in);
+            debug_assert_eq!(vector_in, expected);
+        }
+    }
+    #[test]
+    fn test_pairwise_schedule() {
+        let mut vector_in: Vec<i32> = (0..37).rev().collect();
+        let network = crate::network::Network::pairwise(vector_in.len());
+        for layer in network.layers() {
+            let mut used = vec![false; vector_in.len()];
+            for &(i, j) in layer {
+                debug_assert!(!used[i] && !used[j]);
+                used[i] = true;
+                used[j] = true;
+            }
+        }
+        network.apply(&mut vector_in);
+        debug_assert_eq!(vector_in, (0..37).collect::<Vec<i32>>());
+    }
+    #[test]
+    fn test_pairwise_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        pairwise_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_pairwise_len1() {
+        let mut vector_in = vec![1];
+        pairwise_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
@@ -18,7 +18,6 @@
 /// sorting_rs::pancake_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn pancake_sort<T: PartialOrd + Copy>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -36,9 +35,9 @@ fn largest_pancake<T: PartialOrd + Copy>(input: &[T], index: usize)
     let mut pancake = input[index];
     let mut largest = index;
 
-    for i in 0..index {
-        if input[i] > pancake {
-            pancake = input[i];
+    for (i, &item) in input.iter().enumerate().take(index) {
+        if item > pancake {
+            pancake = item;
             largest = i;
         }
     }
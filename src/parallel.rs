@@ -0,0 +1,637 @@
+use crate::network::default_threads;
+use crate::radix_sort::{count_bytes, is_trivial, offsets, Counts, RadixKey};
+use std::cmp::min;
+
+/// Sorts a slice in-place on several threads using fork-join versions of
+/// [Merge sort](https://en.wikipedia.org/wiki/Merge_sort#Parallel_merge_sort),
+/// [Quicksort](https://en.wikipedia.org/wiki/Quicksort#Parallelization),
+/// [Heapsort](https://en.wikipedia.org/wiki/Heapsort) and
+/// [Introsort](https://en.wikipedia.org/wiki/Introsort).
+/// All kinds of slices can be sorted as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html)
+/// and can be sent between threads. Merge sort additionally needs
+/// [`Copy`](https://doc.rust-lang.org/std/marker/trait.Copy.html) and
+/// [`Sync`](https://doc.rust-lang.org/std/marker/trait.Sync.html).
+///
+/// The module is available with `parallel` feature, which doesn't add any
+/// dependencies: threads are spawned with `std::thread::scope`. Functions use
+/// one thread per available core, `_with` versions take the number of
+/// threads. Recursion forks a thread for one half of the work and continues
+/// with the other one in the current thread, the threads are divided between
+/// the halves, and ranges left with one thread or less than `PAR_MIN_LEN`
+/// elements are sorted sequentially.
+///
+/// `par_merge_sort` sorts halves in parallel, then merges them in parallel
+/// too: output is split into equal parts, and for each part the position of
+/// its first element in both halves is found by binary search along the
+/// diagonal of the merge path, so every thread merges the same number of
+/// elements. The merge is stable.
+///
+/// `par_quick_sort` partitions around median of three into elements less
+/// than, equal to and greater than the pivot. The smaller side is forked
+/// with its share of threads, or sorted in the current thread if the share
+/// is zero, and the current thread loops on the larger side with the rest
+/// of the threads. Like `par_intro_sort`, which partitions like
+/// `intro_sort`, it falls back to parallel heapsort when partitions get too
+/// deep, so both are `nlogn` in the worst case.
+///
+/// `par_sample_sort` distributes elements like `sample_sort`: stripes of the
+/// slice are classified on different threads, then all threads move blocks
+/// to their buckets. Buckets larger than a share of one thread are sorted
+/// in parallel again, smaller ones are picked by the threads from a common
+/// queue, largest first.
+///
+/// `par_radix_sort` splits the slice into one part per thread. Before every
+/// pass the threads count the bytes of their parts, the counts are combined
+/// into offsets, where elements of every part with every value of the byte
+/// go, and then the threads distribute their parts to the buffer at the same
+/// time. Parts are ordered, so the sort stays stable. It takes integers and
+/// floats, and `Copy` types with a key with `par_radix_sort_by_key`.
+///
+/// `par_heap_sort` builds the heap in parallel: subtrees below the top levels
+/// are disjoint, so they are heapified by different threads, and only the
+/// top levels are heapified sequentially. Extraction of elements is
+/// sequential.
+///
+/// # Examples
+/// ```rust
+/// let mut vec: Vec<u64> = (0..100_000).map(|x| x * 7919 % 100_003).collect();
+/// sorting_rs::par_merge_sort(&mut vec);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// sorting_rs::par_quick_sort(&mut strings);
+/// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
+/// ```
+/// ```rust
+/// let mut vec: Vec<i32> = (0..10_000).rev().collect();
+/// sorting_rs::par_intro_sort_with(&mut vec, 4);
+/// assert_eq!(vec, (0..10_000).collect::<Vec<i32>>());
+/// ```
+/// ```rust
+/// let mut vec: Vec<f64> = (0..100_000).map(|x| (x % 1000) as f64).collect();
+/// sorting_rs::par_sample_sort(&mut vec);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+/// ```rust
+/// let mut vec: Vec<i64> = (0..100_000).map(|x| x * 7919 % 100_003 - 50_000)
+///     .collect();
+/// sorting_rs::par_radix_sort(&mut vec);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+/// ```rust
+/// let mut vec = vec![5, 3, 2, 4];
+/// sorting_rs::par_heap_sort_with(&mut vec, 2);
+/// assert_eq!(vec, &[2, 3, 4, 5]);
+/// ```
+pub fn par_merge_sort<T: PartialOrd + Copy + Send + Sync>(input: &mut [T]) {
+    par_merge_sort_with(input, default_threads());
+}
+
+/// Ranges shorter than this are not split between threads.
+pub const PAR_MIN_LEN: usize = 4096;
+
+pub fn par_merge_sort_with<T: PartialOrd + Copy + Send + Sync>(
+input: &mut [T], threads: usize) {
+    if threads <= 1 || input.len() < PAR_MIN_LEN {
+        crate::merge_sort(input);
+        return;
+    }
+
+    let mut buffer = input.to_vec();
+    par_merge_sort_rec(input, &mut buffer, threads);
+}
+
+fn par_merge_sort_rec<T: PartialOrd + Copy + Send + Sync>(input: &mut [T],
+buffer: &mut [T], threads: usize) {
+    if threads <= 1 || input.len() < PAR_MIN_LEN {
+        crate::merge_sort_with_buffer(input, buffer);
+        return;
+    }
+
+    let mid = input.len() / 2;
+    let (left, right) = input.split_at_mut(mid);
+    let (left_buffer, right_buffer) = buffer.split_at_mut(mid);
+    std::thread::scope(|scope| {
+        scope.spawn(|| par_merge_sort_rec(left, left_buffer, threads / 2));
+        par_merge_sort_rec(right, right_buffer, threads - threads / 2);
+    });
+
+    // Merging and copying are split into parts of at least `PAR_MIN_LEN`
+    let parts = min(threads, input.len() / PAR_MIN_LEN);
+    par_merge(&input[..mid], &input[mid..], buffer, parts);
+    let chunk = input.len().div_ceil(parts);
+    std::thread::scope(|scope| {
+        for (to, from) in input.chunks_mut(chunk).zip(buffer.chunks(chunk)) {
+            scope.spawn(move || to.copy_from_slice(from));
+        }
+    });
+}
+
+/// Merges two sorted slices into `output` on `threads` threads.
+fn par_merge<T: PartialOrd + Copy + Send + Sync>(left: &[T], right: &[T],
+output: &mut [T], threads: usize) {
+    let chunk = output.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        for (k, part) in output.chunks_mut(chunk).enumerate() {
+            let start = k * chunk;
+            let (i, j) = merge_path(left, right, start);
+            let (end_i, end_j) = merge_path(left, right, start + part.len());
+            scope.spawn(move || merge(&left[i..end_i], &right[j..end_j],
+                part));
+        }
+    });
+}
+
+/// Returns numbers of elements taken from both slices to form the first
+/// `diagonal` elements of the merged sequence. Elements of the left slice go
+/// first among equal ones.
+fn merge_path<T: PartialOrd>(left: &[T], right: &[T], diagonal: usize)
+-> (usize, usize) {
+    let mut low = diagonal.saturating_sub(right.len());
+    let mut high = min(diagonal, left.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if left[mid] <= right[diagonal - mid - 1] {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    (low, diagonal - low)
+}
+
+fn merge<T: PartialOrd + Copy>(left: &[T], right: &[T], output: &mut [T]) {
+    let mut i = 0;
+    let mut j = 0;
+    for item in output.iter_mut() {
+        if j == right.len() || (i < left.len() && left[i] <= right[j]) {
+            *item = left[i];
+            i += 1;
+        } else {
+            *item = right[j];
+            j += 1;
+        }
+    }
+}
+
+pub fn par_quick_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_quick_sort_with(input, default_threads());
+}
+
+pub fn par_quick_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    let limit = crate::intro_sort::depth_limit(input.len());
+    par_quick_sort_rec(input, threads, limit);
+}
+
+fn par_quick_sort_rec<T: PartialOrd + Send>(input: &mut [T], threads: usize,
+mut limit: usize) {
+    std::thread::scope(|scope| {
+        let (mut input, mut threads) = (input, threads);
+        while threads > 1 && input.len() >= PAR_MIN_LEN {
+            if limit == 0 {
+                par_heap_sort_with(input, threads);
+                return;
+            }
+            limit -= 1;
+
+            let pivot = crate::pivot::PivotChooser::new(
+                crate::PivotStrategy::MedianOfThree).choose(input);
+            let (lt, gt) = crate::partition_3way(input, pivot);
+            let (left, rest) = std::mem::take(&mut input).split_at_mut(lt);
+            let right = &mut rest[gt - lt..];
+            let (smaller, larger) = if left.len() <= right.len() {
+                (left, right)
+            } else {
+                (right, left)
+            };
+
+            // Threads are divided proportionally to the sizes of the sides
+            let total = smaller.len() + larger.len();
+            let smaller_threads = (threads * smaller.len() + total / 2)
+                .checked_div(total).unwrap_or(0);
+            if smaller_threads == 0 {
+                crate::quick_3way_sort(smaller);
+            } else {
+                scope.spawn(move ||
+                    par_quick_sort_rec(smaller, smaller_threads, limit));
+                threads -= smaller_threads;
+            }
+            input = larger;
+        }
+        crate::quick_3way_sort(input);
+    });
+}
+
+pub fn par_intro_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_intro_sort_with(input, default_threads());
+}
+
+pub fn par_intro_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    let limit = crate::intro_sort::depth_limit(input.len());
+    par_intro_sort_rec(input, threads, limit);
+}
+
+fn par_intro_sort_rec<T: PartialOrd + Send>(input: &mut [T], threads: usize,
+limit: usize) {
+    if threads <= 1 || input.len() < PAR_MIN_LEN {
+        crate::intro_sort::intro_sort_limited(input, limit);
+    } else if limit == 0 {
+        par_heap_sort_with(input, threads);
+    } else {
+        let pivot = crate::intro_sort::intro_partition(input);
+        let (left, rest) = input.split_at_mut(pivot);
+        let right = &mut rest[1..];
+
+        let total = left.len() + right.len();
+        let left_threads = (threads * left.len() + total / 2) / total;
+        std::thread::scope(|scope| {
+            scope.spawn(|| par_intro_sort_rec(left, left_threads, limit - 1));
+            par_intro_sort_rec(right, threads - left_threads, limit - 1);
+        });
+    }
+}
+
+pub fn par_sample_sort<T: PartialOrd + Copy + Send + Sync>(input: &mut [T]) {
+    par_sample_sort_with(input, default_threads());
+}
+
+pub fn par_sample_sort_with<T: PartialOrd + Copy + Send + Sync>(
+input: &mut [T], threads: usize) {
+    let len = input.len();
+    if threads <= 1 || len < PAR_MIN_LEN {
+        crate::sample_sort(input);
+        return;
+    }
+
+    let bounds = crate::sample_sort::par_partition(input, threads);
+    // Odd buckets contain elements equal to splitters
+    let mut rest = input;
+    let mut buckets = Vec::new();
+    for b in 0..bounds.len() - 1 {
+        let (bucket, tail) = std::mem::take(&mut rest)
+            .split_at_mut(bounds[b + 1] - bounds[b]);
+        rest = tail;
+        if b % 2 == 0 && bucket.len() > 1 {buckets.push(bucket);}
+    }
+
+    buckets.sort_unstable_by_key(|bucket| bucket.len());
+    while buckets.last().is_some_and(|bucket| bucket.len() > len / threads) {
+        let bucket = buckets.pop().unwrap();
+        // Inconsistent comparisons, like with NaNs, may put all elements
+        // into one bucket, which would recurse forever
+        if bucket.len() == len {
+            crate::par_intro_sort_with(bucket, threads);
+        } else {
+            par_sample_sort_with(bucket, threads);
+        }
+    }
+    let buckets = std::sync::Mutex::new(buckets);
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let bucket = buckets.lock().unwrap().pop();
+                match bucket {
+                    Some(bucket) => crate::sample_sort(bucket),
+                    None => break,
+                }
+            });
+        }
+    });
+}
+
+pub fn par_radix_sort<T: RadixKey + Send + Sync>(input: &mut [T]) {
+    par_radix_sort_by_key_with(input, default_threads(), |item| *item);
+}
+
+pub fn par_radix_sort_with<T: RadixKey + Send + Sync>(input: &mut [T],
+threads: usize) {
+    par_radix_sort_by_key_with(input, threads, |item| *item);
+}
+
+pub fn par_radix_sort_by_key<T, K, F>(input: &mut [T], key: F)
+where T: Copy + Send + Sync, K: RadixKey, F: Fn(&T) -> K + Sync {
+    par_radix_sort_by_key_with(input, default_threads(), key);
+}
+
+pub fn par_radix_sort_by_key_with<T, K, F>(input: &mut [T], threads: usize,
+key: F)
+where T: Copy + Send + Sync, K: RadixKey, F: Fn(&T) -> K + Sync {
+    let len = input.len();
+    if threads <= 1 || len < PAR_MIN_LEN {
+        crate::radix_sort_by_key(input, key);
+        return;
+    }
+
+    let chunk = len.div_ceil(min(threads, len / PAR_MIN_LEN));
+    let part_counts: Vec<Vec<Counts>> = std::thread::scope(|scope| {
+        let key = &key;
+        let handles: Vec<_> = input.chunks(chunk)
+            .map(|part| scope.spawn(move || count_bytes(part, key))).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    let mut counts = vec![[0; 256]; K::BYTES];
+    for part in &part_counts {
+        for (total, count) in counts.iter_mut().zip(part) {
+            for (t, c) in total.iter_mut().zip(count.iter()) {*t += c;}
+        }
+    }
+
+    let mut buffer = par_buffer(input, chunk);
+    let mut from: &mut [T] = input;
+    let mut to: &mut [T] = &mut buffer;
+    let mut moved = false;
+    let mut first = true;
+    for (byte, count) in counts.iter().enumerate() {
+        if is_trivial(count, len) {continue;}
+        // Counts of the first pass are still valid, the others are recounted
+        let part_counts: Vec<Counts> = if !first {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = from.chunks(chunk).map(|part| {
+                    let key = &key;
+                    scope.spawn(move || {
+                        let mut count = [0; 256];
+                        for item in part {
+                            count[key(item).byte(byte) as usize] += 1;
+                        }
+                        count
+                    })
+                }).collect();
+                handles.into_iter().map(|handle| handle.join().unwrap())
+                    .collect()
+            })
+        } else {
+            part_counts.iter().map(|part| part[byte]).collect()
+        };
+
+        // Elements of part `p` with value `v` go after the elements with
+        // smaller values and after the elements of earlier parts with `v`
+        let mut starts = offsets(count);
+        let part_offsets: Vec<Counts> = part_counts.iter().map(|part| {
+            let start = starts;
+            for (s, c) in starts.iter_mut().zip(part.iter()) {*s += c;}
+            start
+        }).collect();
+
+        let output = SharedOutput(to.as_mut_ptr());
+        std::thread::scope(|scope| {
+            for (part, mut offsets) in from.chunks(chunk).zip(part_offsets) {
+                let (key, output) = (&key, &output);
+                scope.spawn(move || for item in part {
+                    let digit = key(item).byte(byte) as usize;
+                    // Offsets of different parts and values don't overlap
+                    unsafe {output.0.add(offsets[digit]).write(*item);}
+                    offsets[digit] += 1;
+                });
+            }
+        });
+        std::mem::swap(&mut from, &mut to);
+        moved = !moved;
+        first = false;
+    }
+    if moved {
+        std::thread::scope(|scope| {
+            for (to, from) in to.chunks_mut(chunk).zip(from.chunks(chunk)) {
+                scope.spawn(move || to.copy_from_slice(from));
+            }
+        });
+    }
+}
+
+/// Copies the slice to a new vector on one thread per `chunk` elements.
+fn par_buffer<T: Copy + Send + Sync>(input: &[T], chunk: usize) -> Vec<T> {
+    let mut buffer = Vec::with_capacity(input.len());
+    std::thread::scope(|scope| {
+        for (to, from) in buffer.spare_capacity_mut().chunks_mut(chunk)
+            .zip(input.chunks(chunk)) {
+            scope.spawn(move || for (t, f) in to.iter_mut().zip(from) {
+                t.write(*f);
+            });
+        }
+    });
+    // All elements were initialized above
+    unsafe {buffer.set_len(input.len());}
+    buffer
+}
+
+/// Output of the distribution, where threads write to distinct positions.
+struct SharedOutput<T>(*mut T);
+
+unsafe impl<T: Send> Sync for SharedOutput<T> {}
+
+pub fn par_heap_sort<T: PartialOrd + Send>(input: &mut [T]) {
+    par_heap_sort_with(input, default_threads());
+}
+
+pub fn par_heap_sort_with<T: PartialOrd + Send>(input: &mut [T],
+threads: usize) {
+    let len = input.len();
+    if len < 2 {return;}
+
+    if threads <= 1 || len < PAR_MIN_LEN {
+        crate::make_heap(input);
+    } else {
+        par_heapify(input, threads);
+    }
+    for end in (1..len).rev() {
+        input.swap(0, end);
+        // Safety: root and its descendants are within the slice
+        unsafe {sift_down(input.as_mut_ptr(), end, 0);}
+    }
+}
+
+/// Pointer to the elements of a heap shared by threads, which sift down in
+/// disjoint subtrees.
+struct SharedHeap<T>(*mut T);
+
+unsafe impl<T: Send> Sync for SharedHeap<T> {}
+
+fn par_heapify<T: PartialOrd + Send>(input: &mut [T], threads: usize) {
+    let len = input.len();
+
+    // Level with at least four subtrees per thread, so they can be balanced,
+    // but not more subtrees than half of the nodes
+    let subtrees = threads.saturating_mul(4).min(len / 2 + 1);
+    let mut level = 0;
+    while 1usize.checked_shl(level).is_some_and(|n| n < subtrees) {level += 1;}
+    let first = (1 << level) - 1;
+    let roots = min(first + 1, len.saturating_sub(first));
+    let threads = min(threads, roots);
+
+    let heap = SharedHeap(input.as_mut_ptr());
+    let heap = &heap;
+    std::thread::scope(|scope| {
+        for t in 0..threads {
+            scope.spawn(move || {
+                for root in (first + t..first + roots).step_by(threads) {
+                    // Nodes of the subtree at depth `d` below the root are
+                    // the contiguous range starting at `root * 2^d + 2^d - 1`
+                    let mut levels = Vec::new();
+                    let (mut start, mut width) = (root, 1);
+                    while start < len {
+                        levels.push((start, min(start + width, len)));
+                        start = start * 2 + 1;
+                        width *= 2;
+                    }
+                    for &(start, end) in levels.iter().rev() {
+                        for node in (start..end).rev() {
+                            // Safety: sift down from a node stays in its
+                            // subtree, subtrees of different roots are
+                            // disjoint
+                            unsafe {sift_down(heap.0, len, node);}
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    for node in (0..min(first, len)).rev() {
+        unsafe {sift_down(heap.0, len, node);}
+    }
+}
+
+/// Sifts element at `root` down the max-heap of `len` elements. Only the
+/// subtree of `root` is accessed.
+unsafe fn sift_down<T: PartialOrd>(heap: *mut T, len: usize,
+mut root: usize) {
+    loop {
+        let mut child = root * 2 + 1;
+        if child >= len {break;}
+        if child + 1 < len && *heap.add(child + 1) > *heap.add(child) {
+            child += 1;
+        }
+        if *heap.add(root) < *heap.add(child) {
+            std::ptr::swap(heap.add(root), heap.add(child));
+            root = child;
+        } else {break;}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> Vec<Vec<i64>> {
+        vec![
+            vec![],
+            vec![1],
+            (0..100).map(|x| (x * 37) % 101).collect(),
+            (0..50_000).map(|x| (x * 7919) % 50_021).collect(),
+            (0..50_000).collect(),
+            (0..50_000).rev().collect(),
+            (0..50_000).map(|x| x % 3).collect(),
+            vec![5; 20_000],
+        ]
+    }
+
+    fn check<F: Fn(&mut [i64], usize)>(sort: F) {
+        for input in inputs() {
+            let mut expected = input.clone();
+            expected.sort();
+            for threads in [1, 2, 3, 8].iter() {
+                let mut vector_in = input.clone();
+                sort(&mut vector_in, *threads);
+                debug_assert_eq!(vector_in, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_merge() {
+        check(par_merge_sort_with);
+        let mut vector_in = vec![3, 1, 2];
+        par_merge_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 2, 3]);
+    }
+    #[test]
+    fn test_par_merge_stable() {
+        let mut vector_in: Vec<(i32, usize)> = (0..20_000)
+            .map(|x| ((x * 37 % 101) as i32, x)).collect();
+        par_merge_sort_with(&mut vector_in, 4);
+        debug_assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    }
+    #[test]
+    fn test_merge_path() {
+        let left = [1, 3, 3, 5];
+        let right = [2, 3, 4];
+        debug_assert_eq!(merge_path(&left, &right, 0), (0, 0));
+        debug_assert_eq!(merge_path(&left, &right, 2), (1, 1));
+        // Equal elements are taken from the left slice first
+        debug_assert_eq!(merge_path(&left, &right, 4), (3, 1));
+        debug_assert_eq!(merge_path(&left, &right, 7), (4, 3));
+    }
+    #[test]
+    fn test_par_quick() {
+        check(par_quick_sort_with);
+        check(|input, threads| par_quick_sort_rec(input, threads, 1));
+        let mut strings = vec!["rustc", "cargo", "rustup"];
+        par_quick_sort(&mut strings);
+        debug_assert_eq!(strings, vec!["cargo", "rustc", "rustup"]);
+    }
+    #[test]
+    fn test_par_intro() {
+        check(par_intro_sort_with);
+        check(|input, threads| par_intro_sort_rec(input, threads, 1));
+        let mut vector_in = vec![3, 1, 2];
+        par_intro_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 2, 3]);
+    }
+    #[test]
+    fn test_par_sample() {
+        check(par_sample_sort_with);
+        let mut vector_in: Vec<i64> = (0..200_000).map(|x| (x * 7919) % 200_003)
+            .collect();
+        let mut expected = vector_in.clone();
+        expected.sort();
+        par_sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, expected);
+
+        let mut vector_in = vec![f64::NAN; 100_000];
+        par_sample_sort_with(&mut vector_in, 4);
+        debug_assert!(vector_in.iter().all(|x| x.is_nan()));
+    }
+    #[test]
+    fn test_par_radix() {
+        check(par_radix_sort_with);
+        let mut floats: Vec<f64> = (0..100_000).map(|x| (x * 7919 % 100_003)
+            as f64 / -3.0).collect();
+        let mut expected = floats.clone();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        par_radix_sort(&mut floats);
+        debug_assert_eq!(floats, expected);
+
+        let mut pairs: Vec<(u128, usize)> = (0..50_000)
+            .map(|i| ((i as u128 * 7919 % 1009) << 70, i)).collect();
+        let mut expected = pairs.clone();
+        expected.sort_by_key(|pair| pair.0);
+        par_radix_sort_by_key_with(&mut pairs, 3, |pair| pair.0);
+        debug_assert_eq!(pairs, expected);
+
+        let mut wide: Vec<u64> = (0..100_000u64)
+            .map(|x| x.wrapping_mul(0x9e37_79b9_7f4a_7c15)).collect();
+        let mut expected = wide.clone();
+        expected.sort();
+        par_radix_sort_with(&mut wide, 4);
+        debug_assert_eq!(wide, expected);
+    }
+    #[test]
+    fn test_par_heap() {
+        check(par_heap_sort_with);
+        let mut vector_in = vec![3, 1, 2];
+        par_heap_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1, 2, 3]);
+    }
+    #[test]
+    fn test_par_heapify() {
+        let mut vector_in: Vec<i64> = (0..10_000).map(|x| (x * 7919) % 10_007)
+            .collect();
+        par_heapify(&mut vector_in, 5);
+        debug_assert!(crate::is_heap(&vector_in));
+        par_heapify(&mut vector_in, usize::MAX);
+        debug_assert!(crate::is_heap(&vector_in));
+    }
+}
@@ -0,0 +1,204 @@
+/// Partitions a slice in-place by predicate: elements for which it returns
+/// `true` are moved before elements for which it returns `false`.
+/// Any kind of slice can be partitioned, elements don't need to implement
+/// any traits.
+///
+/// Partitioning functions return partition point, which is the number of
+/// elements satisfying predicate. Predicate is called exactly once for every
+/// element.
+///
+/// `partition_by` is unstable, it scans from both ends like the Hoare
+/// partition scheme of quicksort, so only misplaced pairs are swapped and the
+/// number of swaps is at most half of the length.
+///
+/// `stable_partition` preserves relative order in both groups. It computes the
+/// destination of every element in a buffer of indexes, then applies this
+/// permutation by following its cycles, which takes linear time and moves
+/// every element at most once.
+///
+/// `stable_partition_in_place` preserves relative order without additional
+/// buffer: halves are partitioned recursively, then the `false` group of the
+/// left half and the `true` group of the right half are exchanged by
+/// rotation, which takes `nlogn` time.
+///
+/// `is_partitioned` checks that no element satisfying predicate follows one
+/// that doesn't, `partition_point` finds partition point of already
+/// partitioned slice using binary search.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![1, 2, 3, 4, 5, 6];
+/// let point = sorting_rs::partition_by(&mut vec, |x| x % 2 == 0);
+/// assert_eq!(point, 3);
+/// assert!(vec[..point].iter().all(|x| x % 2 == 0));
+/// assert!(sorting_rs::is_partitioned(&vec, |x| x % 2 == 0));
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup", "clippy"];
+/// let point = sorting_rs::stable_partition(&mut strings,
+///     |s| s.starts_with("rust"));
+/// assert_eq!(point, 2);
+/// assert_eq!(strings, &["rustc", "rustup", "cargo", "clippy"]);
+/// ```
+/// ```rust
+/// let mut vec = vec![1, 2, 3, 4, 5, 6];
+/// let point = sorting_rs::stable_partition_in_place(&mut vec,
+///     |x| x % 2 == 0);
+/// assert_eq!(vec, &[2, 4, 6, 1, 3, 5]);
+/// assert_eq!(sorting_rs::partition_point(&vec, |x| x % 2 == 0), point);
+/// ```
+pub fn partition_by<T, F: FnMut(&T) -> bool>(input: &mut [T], mut pred: F)
+-> usize {
+    let mut left = 0;
+    let mut right = input.len();
+
+    loop {
+        while left < right && pred(&input[left]) {left += 1;}
+        if left == right {return left;}
+        right -= 1;
+        while left < right && !pred(&input[right]) {right -= 1;}
+        if left == right {return left;}
+        input.swap(left, right);
+        left += 1;
+    }
+}
+
+pub fn stable_partition<T, F: FnMut(&T) -> bool>(input: &mut [T],
+mut pred: F) -> usize {
+    let flags: Vec<bool> = input.iter().map(&mut pred).collect();
+    let point = flags.iter().filter(|f| **f).count();
+
+    let mut dest = Vec::with_capacity(input.len());
+    let mut first = 0;
+    let mut second = point;
+    for flag in flags {
+        if flag {
+            dest.push(first);
+            first += 1;
+        } else {
+            dest.push(second);
+            second += 1;
+        }
+    }
+
+    for i in 0..input.len() {
+        while dest[i] != i {
+            let j = dest[i];
+            input.swap(i, j);
+            dest.swap(i, j);
+        }
+    }
+    point
+}
+
+pub fn stable_partition_in_place<T, F: FnMut(&T) -> bool>(input: &mut [T],
+mut pred: F) -> usize {
+    stable_partition_rotate(input, &mut pred)
+}
+
+fn stable_partition_rotate<T, F: FnMut(&T) -> bool>(input: &mut [T],
+pred: &mut F) -> usize {
+    match input.len() {
+        0 => 0,
+        1 => pred(&input[0]) as usize,
+        len => {
+            let mid = len / 2;
+            let left = stable_partition_rotate(&mut input[..mid], pred);
+            let right = stable_partition_rotate(&mut input[mid..], pred);
+            input[left..mid + right].rotate_left(mid - left);
+            left + right
+        }
+    }
+}
+
+pub fn is_partitioned<T, F: FnMut(&T) -> bool>(input: &[T], mut pred: F)
+-> bool {
+    let mut iter = input.iter();
+    iter.all(&mut pred) || !iter.any(pred)
+}
+
+pub fn partition_point<T, F: FnMut(&T) -> bool>(input: &[T], mut pred: F)
+-> usize {
+    let mut left = 0;
+    let mut right = input.len();
+
+    while left < right {
+        let mid = left + (right - left) / 2;
+        if pred(&input[mid]) {
+            left = mid + 1;
+        } else {
+            right = mid;
+        }
+    }
+    left
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_by() {
+        let mut vector_in = vec![10, 21, 11, 24, 13, 30, 7];
+        let point = partition_by(&mut vector_in, |x| x % 2 == 0);
+        debug_assert_eq!(point, 3);
+        debug_assert!(is_partitioned(&vector_in, |x| x % 2 == 0));
+    }
+    #[test]
+    fn test_partition_by_calls() {
+        let mut vector_in: Vec<i32> = (0..100).collect();
+        let mut calls = 0;
+        partition_by(&mut vector_in, |x| {calls += 1; x % 3 == 0});
+        debug_assert_eq!(calls, 100);
+        debug_assert!(is_partitioned(&vector_in, |x| x % 3 == 0));
+    }
+    #[test]
+    fn test_partition_by_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        debug_assert_eq!(partition_by(&mut vector_in, |x| *x > 0), 0);
+    }
+    #[test]
+    fn test_stable_partition() {
+        let mut vector_in: Vec<(bool, usize)> = (0..100)
+            .map(|x| (x % 3 == 0, x)).collect();
+        let point = stable_partition(&mut vector_in, |x| x.0);
+        debug_assert_eq!(point, 34);
+        debug_assert!(vector_in[..point].iter().all(|x| x.0));
+        debug_assert!(vector_in[..point].windows(2).all(|w| w[0].1 < w[1].1));
+        debug_assert!(vector_in[point..].windows(2).all(|w| w[0].1 < w[1].1));
+    }
+    #[test]
+    fn test_stable_partition_in_place() {
+        let mut vector_in: Vec<(bool, usize)> = (0..100)
+            .map(|x| (x % 3 == 0, x)).collect();
+        let mut calls = 0;
+        let point = stable_partition_in_place(&mut vector_in,
+            |x| {calls += 1; x.0});
+        debug_assert_eq!(calls, 100);
+        debug_assert_eq!(point, 34);
+        debug_assert!(vector_in[..point].iter().all(|x| x.0));
+        debug_assert!(vector_in[..point].windows(2).all(|w| w[0].1 < w[1].1));
+        debug_assert!(vector_in[point..].windows(2).all(|w| w[0].1 < w[1].1));
+    }
+    #[test]
+    fn test_stable_partition_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        debug_assert_eq!(stable_partition(&mut vector_in, |x| *x > 0), 0);
+        debug_assert_eq!(stable_partition_in_place(&mut vector_in,
+            |x| *x > 0), 0);
+    }
+    #[test]
+    fn test_is_partitioned() {
+        debug_assert!(is_partitioned(&[2, 4, 1, 3], |x| x % 2 == 0));
+        debug_assert!(is_partitioned(&[1, 3], |x| x % 2 == 0));
+        debug_assert!(!is_partitioned(&[2, 1, 4], |x| x % 2 == 0));
+        debug_assert!(is_partitioned::<i32, _>(&[], |x| x % 2 == 0));
+    }
+    #[test]
+    fn test_partition_point() {
+        debug_assert_eq!(partition_point(&[1, 2, 3, 7, 8], |x| *x < 5), 3);
+        debug_assert_eq!(partition_point(&[1, 2], |x| *x < 5), 2);
+        debug_assert_eq!(partition_point(&[6, 7], |x| *x < 5), 0);
+        debug_assert_eq!(partition_point::<i32, _>(&[], |x| *x < 5), 0);
+    }
+}
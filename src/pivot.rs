@@ -0,0 +1,147 @@
+/// Pivot selection strategies for the quicksort family and selection.
+///
+/// Quicksort performance depends on how close the pivot is to the median of
+/// the partitioned range. Fixed positions (`First`, `Last`, `Middle`) are the
+/// cheapest, but sorted, reversed or specially crafted input makes them
+/// quadratic. `MedianOfThree` and
+/// [Tukey's ninther](https://en.wikipedia.org/wiki/Median#Median_of_medians)
+/// sample three and nine elements respectively and give pivots much closer to
+/// the median. `Random` picks a pseudo-random position from a generator
+/// seeded with the given value, so runs are reproducible while the input can
+/// not be tuned against the pivot choice without knowing the seed.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::PivotStrategy;
+///
+/// let mut vec = vec![5, 2, 7, 3, 9];
+/// sorting_rs::quick_sort_with(&mut vec, PivotStrategy::MedianOfThree);
+/// assert_eq!(vec, &[2, 3, 5, 7, 9]);
+/// ```
+/// ```rust
+/// use sorting_rs::PivotStrategy;
+///
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// sorting_rs::quick_dual_sort_with(&mut strings, PivotStrategy::Random(42));
+/// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotStrategy {
+    First,
+    #[default]
+    Last,
+    Middle,
+    MedianOfThree,
+    Ninther,
+    Random(u64),
+}
+
+/// Applies strategy to slices, keeps state of the random generator between
+/// calls.
+pub(crate) struct PivotChooser {
+    strategy: PivotStrategy,
+    state: u64,
+}
+
+impl PivotChooser {
+    pub(crate) fn new(strategy: PivotStrategy) -> Self {
+        let state = match strategy {
+            PivotStrategy::Random(seed) => seed,
+            _ => 0,
+        };
+        Self { strategy, state }
+    }
+
+    /// Returns index of the pivot in a non-empty slice.
+    pub(crate) fn choose<T: PartialOrd>(&mut self, input: &[T]) -> usize {
+        let len = input.len();
+        let last = len - 1;
+        match self.strategy {
+            PivotStrategy::First => 0,
+            PivotStrategy::Last => last,
+            PivotStrategy::Middle => len / 2,
+            PivotStrategy::MedianOfThree => {
+                if len < 3 {return last;}
+                median_of_three(input, 0, len / 2, last)
+            },
+            PivotStrategy::Ninther => {
+                if len < 9 {
+                    if len < 3 {return last;}
+                    return median_of_three(input, 0, len / 2, last);
+                }
+                let step = len / 8;
+                let a = median_of_three(input, 0, step, 2 * step);
+                let b = median_of_three(input, 3 * step, 4 * step,
+                    5 * step);
+                let c = median_of_three(input, 6 * step, 7 * step, last);
+                median_of_three(input, a, b, c)
+            },
+            PivotStrategy::Random(_) => {
+                ((self.next_random() as u128 * len as u128) >> 64) as usize
+            },
+        }
+    }
+
+    /// [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator.
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+fn median_of_three<T: PartialOrd>(input: &[T], a: usize, b: usize, c: usize)
+-> usize {
+    if input[a] < input[b] {
+        if input[b] < input[c] {b}
+        else if input[a] < input[c] {c}
+        else {a}
+    } else if input[a] < input[c] {a}
+    else if input[b] < input[c] {c}
+    else {b}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_positions() {
+        let input = [3, 1, 4, 1, 5];
+        debug_assert_eq!(PivotChooser::new(PivotStrategy::First)
+            .choose(&input), 0);
+        debug_assert_eq!(PivotChooser::new(PivotStrategy::Last)
+            .choose(&input), 4);
+        debug_assert_eq!(PivotChooser::new(PivotStrategy::Middle)
+            .choose(&input), 2);
+    }
+    #[test]
+    fn test_median_of_three() {
+        let mut chooser = PivotChooser::new(PivotStrategy::MedianOfThree);
+        debug_assert_eq!(chooser.choose(&[9, 0, 5, 0, 1]), 2);
+        debug_assert_eq!(chooser.choose(&[1, 0, 9, 0, 5]), 4);
+        debug_assert_eq!(chooser.choose(&[5, 0, 9, 0, 1]), 0);
+        debug_assert_eq!(chooser.choose(&[2, 1]), 1);
+    }
+    #[test]
+    fn test_ninther() {
+        let input: Vec<i32> = (0..100).rev().collect();
+        let pivot = PivotChooser::new(PivotStrategy::Ninther).choose(&input);
+        debug_assert!(input[pivot] > 25 && input[pivot] < 75);
+    }
+    #[test]
+    fn test_random_is_seeded() {
+        let input = [0; 1000];
+        let mut a = PivotChooser::new(PivotStrategy::Random(7));
+        let mut b = PivotChooser::new(PivotStrategy::Random(7));
+        let mut c = PivotChooser::new(PivotStrategy::Random(8));
+        let a: Vec<usize> = (0..16).map(|_| a.choose(&input)).collect();
+        let b: Vec<usize> = (0..16).map(|_| b.choose(&input)).collect();
+        let c: Vec<usize> = (0..16).map(|_| c.choose(&input)).collect();
+        debug_assert_eq!(a, b);
+        debug_assert_ne!(a, c);
+        debug_assert!(a.iter().all(|i| *i < input.len()));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::small_sort::{small_sort, SMALL_SORT_LEN};
+use crate::pivot::{PivotChooser, PivotStrategy};
+
 /// Sorts a slice in-place using
 /// [Quick sort](https://en.wikipedia.org/wiki/Quicksort), 
 /// [Dual-Pivot Quicksort](https://www.researchgate.net/publication/259264490_Dual_pivot_Quicksort)
@@ -5,12 +8,40 @@
 /// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
 /// Dual pivot quicksort additionally needs [`Copy`](https://doc.rust-lang.org/std/marker/trait.Copy.html)
 /// 
+/// `quick_sort` pivots on the last element and `quick_dual_sort` on the two
+/// ends of the range. `_with` versions take a
+/// [`PivotStrategy`](crate::PivotStrategy). Dual pivot version applies the
+/// strategy to each half of the range to get left and right pivots.
+///
 /// Quicksort can be compared to merge sort as it also is a divide-and-conquer
 /// algorithm. However, quicksort does all the heavy work before the recursive
 /// calls, so it could also be called a conquer-and-divide algorithm. This
 /// implementation uses the
 /// [Lomuto partition scheme](https://en.wikipedia.org/wiki/Quicksort#Lomuto_partition_scheme).
 ///
+/// `quick_hoare_sort` uses the
+/// [Hoare partition scheme](https://en.wikipedia.org/wiki/Quicksort#Hoare_partition_scheme),
+/// which scans from both ends and swaps only misplaced pairs, so it does about
+/// three times less swaps than Lomuto scheme and splits runs of equal
+/// elements evenly. `quick_3way_sort` uses Dijkstra's
+/// [Dutch national flag](https://en.wikipedia.org/wiki/Dutch_national_flag_problem)
+/// partition into elements less than, equal to and greater than pivot, so
+/// equal elements are excluded from further recursion and input with many
+/// duplicates is sorted in linear time.
+///
+/// Partition schemes are available on their own: `partition_lomuto` and
+/// `partition_hoare` place element at index `pivot` into its final position
+/// and return that position, `partition_3way` returns range of elements equal
+/// to it.
+///
+/// All versions sort the smaller parts recursively and the largest one in a
+/// loop, so the stack depth is at most `log`<sub>2</sub>`n` even on inputs,
+/// which make partitions unbalanced and the time quadratic.
+///
+/// # Panics
+/// Partition functions panic if `pivot` is out of bounds, so also on an
+/// empty slice.
+///
 /// # Examples
 /// ```rust
 /// let mut vec = vec![0, -1, -2, -3,];
@@ -32,17 +63,154 @@
 /// sorting_rs::quick_dual_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
+/// ```rust
+/// use sorting_rs::PivotStrategy;
+///
+/// let mut vec: Vec<i32> = (0..1000).collect();
+/// sorting_rs::quick_sort_with(&mut vec, PivotStrategy::Random(1));
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+/// ```rust
+/// use sorting_rs::PivotStrategy;
+///
+/// let mut vec: Vec<i32> = (0..1000).rev().collect();
+/// sorting_rs::quick_dual_sort_with(&mut vec, PivotStrategy::Ninther);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+/// ```rust
+/// let mut vec = vec![0, -1, -2, -3,];
+/// sorting_rs::quick_hoare_sort(&mut vec);
+/// assert_eq!(vec, &[-3, -2, -1, 0]);
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup", "cargo"];
+/// sorting_rs::quick_3way_sort(&mut strings);
+/// assert_eq!(strings, &["cargo", "cargo", "rustc", "rustup"]);
+/// ```
+/// ```rust
+/// let mut vec = vec![3, 7, 5, 1, 5, 9, 5];
+/// let (lt, gt) = sorting_rs::partition_3way(&mut vec, 2);
+/// assert_eq!((lt, gt), (2, 5));
+/// assert_eq!(&vec[lt..gt], &[5, 5, 5]);
+/// ```
 pub fn quick_sort<T: PartialOrd>(input: &mut [T]) {
-    if input.len() > 1 {
+    quick_sort_with(input, PivotStrategy::Last);
+}
+
+pub fn quick_sort_with<T: PartialOrd>(input: &mut [T],
+strategy: PivotStrategy) {
+    quick_sort_chooser(input, &mut PivotChooser::new(strategy));
+}
+
+fn quick_sort_chooser<T: PartialOrd>(mut input: &mut [T],
+chooser: &mut PivotChooser) {
+    while input.len() > SMALL_SORT_LEN {
+        let pivot = chooser.choose(input);
+        input.swap(pivot, input.len() - 1);
         let pivot = lomuto_partition(input);
-        quick_sort(&mut input[..pivot]);
-        quick_sort(&mut input[pivot + 1..]);
+        let (left, right) = std::mem::take(&mut input).split_at_mut(pivot);
+        let (smaller, larger) = smaller_first(left, &mut right[1..]);
+        quick_sort_chooser(smaller, chooser);
+        input = larger;
+    }
+    small_sort(input);
+}
+
+pub fn quick_hoare_sort<T: PartialOrd>(mut input: &mut [T]) {
+    while input.len() > SMALL_SORT_LEN {
+        let pivot = partition_hoare(input, input.len() / 2);
+        let (left, right) = std::mem::take(&mut input).split_at_mut(pivot);
+        let (smaller, larger) = smaller_first(left, &mut right[1..]);
+        quick_hoare_sort(smaller);
+        input = larger;
+    }
+    small_sort(input);
+}
+
+pub fn quick_3way_sort<T: PartialOrd>(input: &mut [T]) {
+    quick_3way_chooser(input,
+        &mut PivotChooser::new(PivotStrategy::MedianOfThree));
+}
+
+fn quick_3way_chooser<T: PartialOrd>(mut input: &mut [T],
+chooser: &mut PivotChooser) {
+    while input.len() > SMALL_SORT_LEN {
+        let pivot = chooser.choose(input);
+        let (lt, gt) = partition_3way(input, pivot);
+        let (left, right) = std::mem::take(&mut input).split_at_mut(lt);
+        let (smaller, larger) = smaller_first(left, &mut right[gt - lt..]);
+        quick_3way_chooser(smaller, chooser);
+        input = larger;
+    }
+    small_sort(input);
+}
+
+/// Orders two parts by length. Quicksorts recurse into the smaller part and
+/// loop on the larger one, so the recursion is at most `log`<sub>2</sub>`n`
+/// deep.
+fn smaller_first<'a, T>(a: &'a mut [T], b: &'a mut [T])
+-> (&'a mut [T], &'a mut [T]) {
+    if a.len() <= b.len() {(a, b)} else {(b, a)}
+}
+
+pub fn partition_lomuto<T: PartialOrd>(input: &mut [T], pivot: usize)
+-> usize {
+    assert!(pivot < input.len(), "Pivot is out of bounds");
+
+    input.swap(pivot, input.len() - 1);
+    lomuto_partition(input)
+}
+
+pub fn partition_hoare<T: PartialOrd>(input: &mut [T], pivot: usize)
+-> usize {
+    assert!(pivot < input.len(), "Pivot is out of bounds");
+
+    input.swap(0, pivot);
+    let mut i = 0;
+    let mut j = input.len();
+
+    // Both scans stop at elements equal to pivot, so runs of equal elements
+    // are split in the middle
+    loop {
+        i += 1;
+        while i < input.len() && input[i] < input[0] {i += 1;}
+        j -= 1;
+        while input[j] > input[0] {j -= 1;}
+        if i >= j {break;}
+        input.swap(i, j);
+    }
+
+    input.swap(0, j);
+    j
+}
+
+pub fn partition_3way<T: PartialOrd>(input: &mut [T], pivot: usize)
+-> (usize, usize) {
+    assert!(pivot < input.len(), "Pivot is out of bounds");
+
+    input.swap(0, pivot);
+    let mut lt = 0;
+    let mut i = 1;
+    let mut gt = input.len();
+
+    // Pivot is always kept at index lt, as the first of equal elements
+    while i < gt {
+        if input[i] < input[lt] {
+            input.swap(lt, i);
+            lt += 1;
+            i += 1;
+        } else if input[i] > input[lt] {
+            gt -= 1;
+            input.swap(i, gt);
+        } else {
+            i += 1;
+        }
     }
+    (lt, gt)
 }
 
 /// Partitions a slice according to the Lomuto partition scheme.
-fn lomuto_partition<T: PartialOrd>(input: &mut [T]) -> usize {
+pub(crate) fn lomuto_partition<T: PartialOrd>(input: &mut [T]) -> usize {
     let pivot = input.len() - 1;
     let mut swap = 0;
     for i in 0..pivot {
@@ -62,12 +230,42 @@ fn lomuto_partition<T: PartialOrd>(input: &mut [T]) -> usize {
 
 pub fn quick_dual_sort<T: PartialOrd + Copy>(input: &mut [T]) {
     if input.len() < 2 {return;}
-    dual_pivot(input, 0, input.len() - 1);
+    dual_pivot(input, 0, input.len() - 1, &mut None);
 }
 
-fn dual_pivot<T: PartialOrd + Copy>(input: &mut [T], start: usize,
-end: usize) {
-    if start >= end {return;}
+pub fn quick_dual_sort_with<T: PartialOrd + Copy>(input: &mut [T],
+strategy: PivotStrategy) {
+    if input.len() < 2 {return;}
+    let mut chooser = Some(PivotChooser::new(strategy));
+    dual_pivot(input, 0, input.len() - 1, &mut chooser);
+}
+
+/// Sorts range between `start` and `end` inclusive, without chooser pivots
+/// are the ends of the range.
+fn dual_pivot<T: PartialOrd + Copy>(input: &mut [T], mut start: usize,
+mut end: usize, chooser: &mut Option<PivotChooser>) {
+    loop {
+        if start >= end {return;}
+        if end - start < SMALL_SORT_LEN {
+            small_sort(&mut input[start..=end]);
+            return;
+        }
+        (start, end) = dual_pivot_partition(input, start, end, chooser);
+    }
+}
+
+/// Partitions the range around two pivots, sorts the two smaller parts and
+/// returns the largest one. The parts sorted recursively have at most half
+/// of the elements, so the recursion is at most `log`<sub>2</sub>`n` deep.
+fn dual_pivot_partition<T: PartialOrd + Copy>(input: &mut [T], start: usize,
+end: usize, chooser: &mut Option<PivotChooser>) -> (usize, usize) {
+    if let Some(chooser) = chooser {
+        let mid = start + (end - start) / 2;
+        let left = start + chooser.choose(&input[start..=mid]);
+        let right = mid + 1 + chooser.choose(&input[mid + 1..=end]);
+        input.swap(start, left);
+        input.swap(end, right);
+    }
     if input[start] > input[end] {
         input.swap(start, end);
     }
@@ -89,6 +287,7 @@ end: usize) {
                 endm -= 1;
             }
             input.swap(point, endm);
+            endm -= 1;
 
             if input[point] < lpivot {
                 input.swap(point, startm);
@@ -102,9 +301,12 @@ end: usize) {
     input.swap(start, startm);
     input.swap(end, endm);
 
-    dual_pivot(input, start, startm);
-    dual_pivot(input, startm + 1, endm);
-    dual_pivot(input, endm, end);
+    let mut ranges = [(start, startm), (startm + 1, endm), (endm, end)];
+    ranges.sort_by_key(|&(from, to)| to.saturating_sub(from));
+    for &(from, to) in &ranges[..2] {
+        dual_pivot(input, from, to, chooser);
+    }
+    ranges[2]
 }
 
 #[cfg(test)]
@@ -130,6 +332,125 @@ mod tests {
         debug_assert_eq!(vector_in, vec![1]);
     }
     #[test]
+    fn test_quick_with() {
+        let strategies = [
+            PivotStrategy::First, PivotStrategy::Last, PivotStrategy::Middle,
+            PivotStrategy::MedianOfThree, PivotStrategy::Ninther,
+            PivotStrategy::Random(42),
+        ];
+        for strategy in strategies.iter() {
+            let mut vector_in = vec![10, 20, 11, 24, 22, 21, 19, 10, 4, 3, 2];
+            quick_sort_with(&mut vector_in, *strategy);
+            debug_assert_eq!(vector_in, vec![2, 3, 4, 10, 10, 11, 19, 20, 21,
+                22, 24]);
+            let mut vector_in: Vec<i32> = (0..500).rev().collect();
+            quick_sort_with(&mut vector_in, *strategy);
+            debug_assert_eq!(vector_in, (0..500).collect::<Vec<i32>>());
+        }
+    }
+    #[test]
+    fn test_quick_dual_with() {
+        let strategies = [
+            PivotStrategy::First, PivotStrategy::Last, PivotStrategy::Middle,
+            PivotStrategy::MedianOfThree, PivotStrategy::Ninther,
+            PivotStrategy::Random(42),
+        ];
+        for strategy in strategies.iter() {
+            let mut vector_in = vec![10, 20, 11, 24, 22, 21, 19, 10, 4, 3, 2];
+            quick_dual_sort_with(&mut vector_in, *strategy);
+            debug_assert_eq!(vector_in, vec![2, 3, 4, 10, 10, 11, 19, 20, 21,
+                22, 24]);
+            let mut vector_in: Vec<i32> = (0..500).rev().collect();
+            quick_dual_sort_with(&mut vector_in, *strategy);
+            debug_assert_eq!(vector_in, (0..500).collect::<Vec<i32>>());
+        }
+    }
+    #[test]
+    fn test_quick_hoare() {
+        let mut vector_in = vec![10, 20, 11, 24, 22, 21, 19, 10, 4, 3, 2];
+        quick_hoare_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![2, 3, 4, 10, 10, 11, 19, 20, 21, 22,
+            24]);
+    }
+    #[test]
+    fn test_quick_hoare_duplicates() {
+        let mut vector_in: Vec<i32> = (0..1000).map(|x| x % 3).collect();
+        quick_hoare_sort(&mut vector_in);
+        debug_assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    }
+    #[test]
+    fn test_quick_hoare_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        quick_hoare_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_quick_hoare_len1() {
+        let mut vector_in = vec![1];
+        quick_hoare_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+    #[test]
+    fn test_quick_3way() {
+        let mut vector_in = vec![10, 20, 11, 24, 22, 21, 19, 10, 4, 3, 2];
+        quick_3way_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![2, 3, 4, 10, 10, 11, 19, 20, 21, 22,
+            24]);
+    }
+    #[test]
+    fn test_quick_3way_duplicates() {
+        let mut vector_in: Vec<i32> = (0..1000).map(|x| x % 3).collect();
+        quick_3way_sort(&mut vector_in);
+        debug_assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    }
+    #[test]
+    fn test_quick_3way_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        quick_3way_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_quick_3way_len1() {
+        let mut vector_in = vec![1];
+        quick_3way_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+    #[test]
+    fn test_partitions() {
+        let base = vec![5, 9, 1, 5, 7, 3, 5, 8, 2, 5];
+        for pivot in 0..base.len() {
+            let value = base[pivot];
+
+            let mut vector_in = base.clone();
+            let p = partition_lomuto(&mut vector_in, pivot);
+            debug_assert_eq!(vector_in[p], value);
+            debug_assert!(vector_in[..p].iter().all(|x| *x <= value));
+            debug_assert!(vector_in[p + 1..].iter().all(|x| *x >= value));
+
+            let mut vector_in = base.clone();
+            let p = partition_hoare(&mut vector_in, pivot);
+            debug_assert_eq!(vector_in[p], value);
+            debug_assert!(vector_in[..p].iter().all(|x| *x <= value));
+            debug_assert!(vector_in[p + 1..].iter().all(|x| *x >= value));
+
+            let mut vector_in = base.clone();
+            let (lt, gt) = partition_3way(&mut vector_in, pivot);
+            debug_assert!(vector_in[..lt].iter().all(|x| *x < value));
+            debug_assert!(vector_in[lt..gt].iter().all(|x| *x == value));
+            debug_assert!(vector_in[gt..].iter().all(|x| *x > value));
+        }
+    }
+    #[test]
+    #[should_panic(expected = "Pivot is out of bounds")]
+    fn test_partition_lomuto_empty() {
+        partition_lomuto::<i32>(&mut [], 0);
+    }
+    #[test]
+    #[should_panic(expected = "Pivot is out of bounds")]
+    fn test_partition_hoare_empty() {
+        partition_hoare::<i32>(&mut [], 0);
+    }
+    #[test]
     fn test_quick_dual() {
         let mut vector_in = vec![10, 20, 11, 24];
         quick_dual_sort(&mut vector_in);
@@ -0,0 +1,296 @@
+/// Sorts a slice in-place using
+/// [LSD Radix sort](https://en.wikipedia.org/wiki/Radix_sort#Least_significant_digit).
+/// Slices of unsigned and signed integers and floats can be sorted, as well
+/// as any type implementing [`RadixKey`](trait.RadixKey.html). Slices of other
+/// [`Copy`](https://doc.rust-lang.org/std/marker/trait.Copy.html) types, like
+/// structs, are sorted by a key with `radix_sort_by_key`.
+///
+/// Radix sort doesn't compare elements: keys are split into bytes, and the
+/// slice is distributed by every byte, from the least significant to the most
+/// significant one, with a counting sort, which is stable, so the order made
+/// by the previous bytes is kept among elements with equal byte. Counts of
+/// all bytes are collected in one pass before sorting, and the bytes, which
+/// are equal in all keys, are skipped, so small keys of wide types take less
+/// passes. Slices of up to 32 elements are sorted by insertion.
+///
+/// Signed integers are sorted as unsigned ones with the sign bit flipped.
+/// Floats are sorted by their bits with all bits of negative numbers and the
+/// sign bit of positive ones flipped, which is the
+/// [total order](https://doc.rust-lang.org/std/primitive.f64.html#method.total_cmp):
+/// `-0.0` goes before `0.0`, and NaNs go to the ends depending on their sign.
+///
+/// The sort is stable, takes `w` passes over the slice for keys of `w` bytes
+/// and needs a buffer of the slice size.
+///
+/// `par_radix_sort` from the `parallel` module counts bytes of parts of the
+/// slice on different threads, combines the counts into offsets of every part
+/// and distributes the parts in parallel.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5u32, 3, 2, 4];
+/// sorting_rs::radix_sort(&mut vec);
+/// assert_eq!(vec, &[2, 3, 4, 5]);
+/// ```
+/// ```rust
+/// let mut vec = vec![0.5, -1.0, 3.25, -0.0, 0.0];
+/// sorting_rs::radix_sort(&mut vec);
+/// assert_eq!(vec, &[-1.0, -0.0, 0.0, 0.5, 3.25]);
+/// ```
+/// ```rust
+/// let mut people = vec![("Ann", 31i8), ("Bob", -2), ("Cid", 31), ("Dan", 7)];
+/// sorting_rs::radix_sort_by_key(&mut people, |person| person.1);
+/// assert_eq!(people, &[("Bob", -2), ("Dan", 7), ("Ann", 31), ("Cid", 31)]);
+/// ```
+pub fn radix_sort<T: RadixKey>(input: &mut [T]) {
+    radix_sort_by_key(input, |item| *item);
+}
+
+pub fn radix_sort_by_key<T: Copy, K: RadixKey, F: Fn(&T) -> K>(
+input: &mut [T], key: F) {
+    if input.len() <= SMALL_LEN {
+        insertion_sort_by_key(input, &key);
+        return;
+    }
+
+    let counts = count_bytes(input, &key);
+    let mut buffer = input.to_vec();
+    let mut from: &mut [T] = input;
+    let mut to: &mut [T] = &mut buffer;
+    let mut moved = false;
+    for (byte, count) in counts.iter().enumerate() {
+        if is_trivial(count, from.len()) {continue;}
+        scatter(from, to, offsets(count), byte, &key);
+        std::mem::swap(&mut from, &mut to);
+        moved = !moved;
+    }
+    // After an odd number of passes sorted elements are in the buffer
+    if moved {to.copy_from_slice(from);}
+}
+
+/// Keys, which can be sorted byte by byte. Order of keys must be the order of
+/// their bytes compared from the most significant one, as unsigned numbers.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::radix_sort::RadixKey;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Version(u16, u16);
+///
+/// impl RadixKey for Version {
+///     const BYTES: usize = 4;
+///     fn byte(&self, i: usize) -> u8 {
+///         (((self.0 as u32) << 16 | self.1 as u32) >> (8 * i)) as u8
+///     }
+/// }
+///
+/// let mut vec = vec![Version(1, 2), Version(0, 9), Version(1, 0)];
+/// sorting_rs::radix_sort(&mut vec);
+/// assert_eq!(vec, &[Version(0, 9), Version(1, 0), Version(1, 2)]);
+/// ```
+pub trait RadixKey: Copy {
+    /// Number of bytes in the key.
+    const BYTES: usize;
+    /// Returns byte `i` of the key, counting from the least significant one.
+    fn byte(&self, i: usize) -> u8;
+}
+
+macro_rules! radix_unsigned {
+    ($($t:ident)*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            #[inline]
+            fn byte(&self, i: usize) -> u8 {
+                (*self >> (8 * i)) as u8
+            }
+        }
+    )*};
+}
+
+macro_rules! radix_signed {
+    ($($t:ident => $u:ident)*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            #[inline]
+            fn byte(&self, i: usize) -> u8 {
+                ((*self as $u ^ !(<$u>::MAX >> 1)) >> (8 * i)) as u8
+            }
+        }
+    )*};
+}
+
+macro_rules! radix_float {
+    ($($t:ident => $u:ident)*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+            #[inline]
+            fn byte(&self, i: usize) -> u8 {
+                let bits = self.to_bits();
+                let sign = !(<$u>::MAX >> 1);
+                let bits = if bits & sign != 0 {!bits} else {bits | sign};
+                (bits >> (8 * i)) as u8
+            }
+        }
+    )*};
+}
+
+radix_unsigned!(u8 u16 u32 u64 u128 usize);
+radix_signed!(i8 => u8 i16 => u16 i32 => u32 i64 => u64 i128 => u128
+    isize => usize);
+radix_float!(f32 => u32 f64 => u64);
+
+/// Slices up to this length are sorted by insertion.
+const SMALL_LEN: usize = 32;
+
+/// Counts of the values of one byte.
+pub(crate) type Counts = [usize; 256];
+
+fn insertion_sort_by_key<T: Copy, K: RadixKey, F: Fn(&T) -> K>(
+input: &mut [T], key: &F) {
+    for i in 1..input.len() {
+        let item = input[i];
+        let mut j = i;
+        while j > 0 && less(&key(&item), &key(&input[j - 1])) {
+            input[j] = input[j - 1];
+            j -= 1;
+        }
+        input[j] = item;
+    }
+}
+
+/// Compares keys by their bytes from the most significant one.
+fn less<K: RadixKey>(a: &K, b: &K) -> bool {
+    for i in (0..K::BYTES).rev() {
+        let (x, y) = (a.byte(i), b.byte(i));
+        if x != y {return x < y;}
+    }
+    false
+}
+
+/// Moves elements to `output` by the value of the byte starting at `offsets`.
+fn scatter<T: Copy, K: RadixKey, F: Fn(&T) -> K>(input: &[T], output: &mut [T],
+mut offsets: Counts, byte: usize, key: &F) {
+    for item in input {
+        let digit = key(item).byte(byte) as usize;
+        output[offsets[digit]] = *item;
+        offsets[digit] += 1;
+    }
+}
+
+/// Counts values of all bytes of the keys in one pass.
+pub(crate) fn count_bytes<T, K: RadixKey, F: Fn(&T) -> K>(input: &[T],
+key: &F) -> Vec<Counts> {
+    let mut counts = vec![[0; 256]; K::BYTES];
+    for item in input {
+        let key = key(item);
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[key.byte(byte) as usize] += 1;
+        }
+    }
+    counts
+}
+
+/// Checks if all `len` keys have the same value of the byte, so the pass
+/// wouldn't move anything.
+pub(crate) fn is_trivial(count: &Counts, len: usize) -> bool {
+    count.contains(&len)
+}
+
+/// Returns the positions, where keys with every value of the byte start.
+pub(crate) fn offsets(count: &Counts) -> Counts {
+    let mut offsets = [0; 256];
+    let mut sum = 0;
+    for (offset, c) in offsets.iter_mut().zip(count.iter()) {
+        *offset = sum;
+        sum += c;
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radix() {
+        let mut vector_in = vec![10u32, 20, 11, 24, 15];
+        radix_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 15, 20, 24]);
+    }
+    #[test]
+    fn test_radix_types() {
+        let mut unsigned: Vec<u64> = (0..10_000u64)
+            .map(|x| x.wrapping_mul(0x9e37_79b9_7f4a_7c15)).collect();
+        let mut expected = unsigned.clone();
+        expected.sort();
+        radix_sort(&mut unsigned);
+        debug_assert_eq!(unsigned, expected);
+
+        let mut wide: Vec<u128> = (0..10_000u128)
+            .map(|x| (x * 7919 % 10_007) << 64 | x).collect();
+        let mut expected = wide.clone();
+        expected.sort();
+        radix_sort(&mut wide);
+        debug_assert_eq!(wide, expected);
+
+        let mut signed: Vec<i32> = (-5000..5000).map(|x| x * 7919 % 10_007)
+            .collect();
+        signed.extend_from_slice(&[i32::MIN, i32::MAX, 0, -1]);
+        let mut expected = signed.clone();
+        expected.sort();
+        radix_sort(&mut signed);
+        debug_assert_eq!(signed, expected);
+
+        let mut small: Vec<i8> = (0..1000).map(|x| (x * 37 % 256) as i8)
+            .collect();
+        let mut expected = small.clone();
+        expected.sort();
+        radix_sort(&mut small);
+        debug_assert_eq!(small, expected);
+    }
+    #[test]
+    fn test_radix_floats() {
+        let mut floats: Vec<f64> = (-500..500).map(|x| (x * 7919 % 1009) as f64
+            / 7.0).collect();
+        floats.extend_from_slice(&[f64::INFINITY, f64::NEG_INFINITY, -0.0,
+            f64::MIN_POSITIVE, -f64::MIN_POSITIVE, f64::MAX, f64::MIN]);
+        let mut expected = floats.clone();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        radix_sort(&mut floats);
+        debug_assert_eq!(floats, expected);
+
+        let mut floats: Vec<f32> = vec![1.5, f32::NAN, -2.0, -0.0, 0.0];
+        radix_sort(&mut floats);
+        debug_assert_eq!(&floats[..4], &[-2.0, -0.0, 0.0, 1.5]);
+        debug_assert!(floats[4].is_nan());
+    }
+    #[test]
+    fn test_radix_by_key_stable() {
+        let mut vector_in: Vec<(i16, usize)> = (0..5000)
+            .map(|i| ((i * 7919 % 211) as i16 - 100, i)).collect();
+        let mut expected = vector_in.clone();
+        expected.sort_by_key(|item| item.0);
+        radix_sort_by_key(&mut vector_in, |item| item.0);
+        debug_assert_eq!(vector_in, expected);
+
+        let mut vector_in = expected[..20].to_vec();
+        vector_in.reverse();
+        expected = vector_in.clone();
+        expected.sort_by_key(|item| item.0);
+        radix_sort_by_key(&mut vector_in, |item| item.0);
+        debug_assert_eq!(vector_in, expected);
+    }
+    #[test]
+    fn test_radix_empty() {
+        let mut vector_in:Vec<u32> = vec![];
+        radix_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_radix_len1() {
+        let mut vector_in = vec![1u32];
+        radix_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
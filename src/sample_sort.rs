@@ -0,0 +1,536 @@
+use crate::intro_sort;
+use crate::pivot::{PivotChooser, PivotStrategy};
+use std::cmp::{max, min};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+
+/// Sorts a slice in-place using
+/// [Samplesort](https://en.wikipedia.org/wiki/Samplesort) in the style of
+/// [IPS⁴o](https://arxiv.org/abs/1705.02257), in-place parallel super scalar
+/// samplesort.
+/// All kinds of slices can be sorted as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html)
+/// and [`Copy`](https://doc.rust-lang.org/std/marker/trait.Copy.html).
+///
+/// Samplesort generalizes quicksort to many pivots: up to 255 splitters are
+/// picked from a sorted random sample, which is several times larger than
+/// the number of splitters, so buckets get nearly equal sizes. The sample is
+/// picked with a new seed from `RandomState` on every call, so it can't be
+/// predicted from the input. Elements are distributed into the buckets,
+/// which are then sorted recursively, ranges of up to 4096 elements are
+/// sorted with `intro_sort`.
+///
+/// Splitters are stored as a complete binary search tree in breadth-first
+/// order, so an element is classified by descending the tree, and the next
+/// node is computed from the comparison result without branches. Every
+/// splitter also gets a bucket for elements equal to it, such buckets are not
+/// sorted further, so input with many duplicates is handled in linear time.
+///
+/// Distribution is done in place with blocks of about 2 KiB. Every bucket has
+/// a buffer of one block: classified elements are collected in the buffers,
+/// and full buffers are written back to the already scanned part of the
+/// slice. Then the blocks are permuted to the block-aligned ranges of their
+/// buckets, and the elements of partial blocks are moved to the ends of the
+/// buckets. Additional memory is `O(kB)` for `k` buckets and blocks of `B`
+/// elements instead of `O(n)` of classic samplesort.
+///
+/// `par_sample_sort` from the `parallel` module classifies stripes of the
+/// slice on different threads and permutes blocks on all of them, then
+/// sorts the buckets in parallel.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5, 3, 2, 4];
+/// sorting_rs::sample_sort(&mut vec);
+/// assert_eq!(vec, &[2, 3, 4, 5]);
+/// ```
+/// ```rust
+/// let mut vec: Vec<u64> = (0..100_000).map(|x| x * 7919 % 100_003).collect();
+/// sorting_rs::sample_sort(&mut vec);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn sample_sort<T: PartialOrd + Copy>(input: &mut [T]) {
+    if input.len() <= BASE_LEN {
+        intro_sort(input);
+        return;
+    }
+
+    let bounds = partition(input);
+    let len = input.len();
+    // Odd buckets contain elements equal to splitters
+    for b in (0..bounds.len() - 1).step_by(2) {
+        let bucket = &mut input[bounds[b]..bounds[b + 1]];
+        // Inconsistent comparisons, like with NaNs, may put all elements
+        // into one bucket, which would recurse forever
+        if bucket.len() == len {
+            intro_sort(bucket);
+        } else {
+            sample_sort(bucket);
+        }
+    }
+}
+
+/// Ranges of up to this length are sorted with `intro_sort`.
+pub(crate) const BASE_LEN: usize = 4096;
+
+/// Size of blocks moved during distribution.
+const BLOCK_BYTES: usize = 2048;
+
+/// Splitters stored as a complete binary search tree in breadth-first order,
+/// so classification descends it without branches.
+struct Classifier<T> {
+    tree: Vec<T>,
+    splitters: Vec<T>,
+    log_k: u32,
+}
+
+impl<T: PartialOrd + Copy> Classifier<T> {
+    /// Sorts random sample at the front of the slice and picks evenly spaced
+    /// distinct splitters from it.
+    fn new(input: &mut [T]) -> Self {
+        let n = input.len();
+        let log_k = min(8, (n / 256).ilog2());
+        let k = 1 << log_k;
+        let oversampling = max(1, n.ilog2() as usize / 5);
+        let sample_len = min(n / 2, oversampling * k - 1);
+
+        // Seed differs between calls, so crafted input can't predict the
+        // sample and make the buckets unbalanced
+        let seed = RandomState::new().hash_one(n);
+        let mut chooser = PivotChooser::new(PivotStrategy::Random(seed));
+        for i in 0..sample_len {
+            let j = i + chooser.choose(&input[i..]);
+            input.swap(i, j);
+        }
+        intro_sort(&mut input[..sample_len]);
+
+        let mut splitters: Vec<T> = Vec::with_capacity(k - 1);
+        for j in 1..k {
+            let splitter = input[j * oversampling - 1];
+            if splitters.last().is_none_or(|last| *last < splitter) {
+                splitters.push(splitter);
+            }
+        }
+
+        // Tree is padded with the largest splitter if some of them were equal
+        let log_k = usize::BITS - splitters.len().leading_zeros();
+        let mut sorted = splitters.clone();
+        sorted.resize((1 << log_k) - 1, splitters[splitters.len() - 1]);
+        let mut tree = vec![sorted[0]; 1 << log_k];
+        fill_tree(&mut tree, &sorted, 1, &mut 0);
+        Self { tree, splitters, log_k }
+    }
+
+    fn buckets(&self) -> usize {
+        2 << self.log_k
+    }
+
+    /// Returns `2b` for elements between splitters `b - 1` and `b`, `2b + 1`
+    /// for elements equal to splitter `b`.
+    #[inline]
+    fn classify(&self, item: &T) -> usize {
+        let mut i = 1;
+        for _ in 0..self.log_k {
+            i = 2 * i + (self.tree[i] < *item) as usize;
+        }
+        let b = i - (1 << self.log_k);
+        2 * b + (b < self.splitters.len() && self.splitters[b] <= *item)
+            as usize
+    }
+}
+
+/// Places sorted values into the tree by in-order traversal.
+fn fill_tree<T: Copy>(tree: &mut [T], sorted: &[T], node: usize,
+next: &mut usize) {
+    if node < tree.len() {
+        fill_tree(tree, sorted, 2 * node, next);
+        tree[node] = sorted[*next];
+        *next += 1;
+        fill_tree(tree, sorted, 2 * node + 1, next);
+    }
+}
+
+fn block_len<T>(n: usize, buckets: usize) -> usize {
+    let len = BLOCK_BYTES / max(1, std::mem::size_of::<T>());
+    max(1, min(len, n / (4 * buckets)))
+}
+
+/// Result of classifying a stripe: full blocks are written back to the
+/// beginning of the stripe, the rest of elements stays in the buffers.
+struct Stripe<T> {
+    /// End of the full blocks in the whole slice.
+    end: usize,
+    sizes: Vec<usize>,
+    buffers: Vec<T>,
+    lens: Vec<usize>,
+}
+
+fn classify_stripe<T: PartialOrd + Copy>(stripe: &mut [T], offset: usize,
+classifier: &Classifier<T>, block: usize) -> Stripe<T> {
+    let buckets = classifier.buckets();
+    let mut buffers = vec![stripe[0]; buckets * block];
+    let mut lens = vec![0; buckets];
+    let mut sizes = vec![0; buckets];
+
+    // Written blocks never reach unread elements, as every element is
+    // copied to a buffer before its block is written
+    let mut write = 0;
+    for i in 0..stripe.len() {
+        let item = stripe[i];
+        let b = classifier.classify(&item);
+        buffers[b * block + lens[b]] = item;
+        lens[b] += 1;
+        sizes[b] += 1;
+        if lens[b] == block {
+            stripe[write..write + block]
+                .copy_from_slice(&buffers[b * block..(b + 1) * block]);
+            write += block;
+            lens[b] = 0;
+        }
+    }
+    Stripe { end: offset + write, sizes, buffers, lens }
+}
+
+/// Moves full blocks of all stripes to the beginning of the slice, returns
+/// their end.
+#[cfg(feature = "parallel")]
+fn compact_blocks<T: Copy>(input: &mut [T], stripes: &[Stripe<T>],
+stripe_len: usize, block: usize) -> usize {
+    let full: usize = stripes.iter().enumerate()
+        .map(|(s, stripe)| stripe.end - s * stripe_len).sum();
+
+    let mut gaps = Vec::new();
+    let mut blocks = Vec::new();
+    for (s, stripe) in stripes.iter().enumerate() {
+        let end = min((s + 1) * stripe_len, input.len());
+        blocks.extend((s * stripe_len..stripe.end).step_by(block)
+            .filter(|pos| *pos >= full));
+        gaps.extend((stripe.end..end).step_by(block)
+            .filter(|pos| *pos < full));
+    }
+    for (from, to) in blocks.into_iter().zip(gaps) {
+        input.copy_within(from..from + block, to);
+    }
+    full
+}
+
+/// Next slots to write and to read in the block-aligned range of a bucket,
+/// slots between them hold blocks which are not permuted yet.
+struct Pointers {
+    write: usize,
+    read: usize,
+}
+
+/// Shape of the partitioned slice.
+struct Layout {
+    /// Starts of buckets and the end of the slice.
+    bounds: Vec<usize>,
+    /// Starts of block-aligned ranges of buckets.
+    delimiters: Vec<usize>,
+}
+
+impl Layout {
+    fn new<T>(stripes: &[Stripe<T>], block: usize) -> Self {
+        let buckets = stripes[0].sizes.len();
+        let mut bounds = vec![0; buckets + 1];
+        for b in 0..buckets {
+            let size: usize = stripes.iter().map(|s| s.sizes[b]).sum();
+            bounds[b + 1] = bounds[b] + size;
+        }
+        let delimiters = bounds.iter().map(|b| b.next_multiple_of(block))
+            .collect();
+        Self { bounds, delimiters }
+    }
+
+    fn pointers(&self, full: usize) -> Vec<Mutex<Pointers>> {
+        self.delimiters.windows(2).map(|d| Mutex::new(Pointers {
+            write: d[0],
+            read: full.clamp(d[0], d[1]),
+        })).collect()
+    }
+}
+
+/// Pointer to the slice shared by threads, which access disjoint blocks or
+/// ranges.
+struct Shared<T>(*mut T);
+
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// Moves blocks to their buckets, starting from bucket `first`. Blocks are
+/// taken from the read end of a bucket and carried to the write slot of
+/// their bucket, block found there is carried further, until an empty slot
+/// is reached. Block which would cross the end of the slice is written to
+/// `overflow`. Several threads can permute at the same time, slots of a
+/// bucket are only accessed under its lock.
+fn permute_blocks<T: PartialOrd + Copy>(data: &Shared<T>, n: usize,
+classifier: &Classifier<T>, block: usize, pointers: &[Mutex<Pointers>],
+overflow: &Mutex<Vec<T>>, first: usize) {
+    let buckets = pointers.len();
+    let mut current = Vec::with_capacity(block);
+    let mut spare = Vec::with_capacity(block);
+
+    for step in 0..buckets {
+        let bucket = (first + step) % buckets;
+        loop {
+            {
+                let mut p = pointers[bucket].lock().unwrap();
+                if p.write >= p.read {break;}
+                p.read -= block;
+                current.clear();
+                current.extend_from_slice(unsafe {block_at(data, p.read,
+                    block)});
+            }
+
+            loop {
+                let dest = classifier.classify(&current[0]);
+                let mut p = pointers[dest].lock().unwrap();
+                while p.write < p.read && classifier.classify(unsafe {
+                    &*data.0.add(p.write)}) == dest {
+                    p.write += block;
+                }
+                let slot = p.write;
+                p.write += block;
+
+                if slot < p.read {
+                    let target = unsafe {block_at(data, slot, block)};
+                    spare.clear();
+                    spare.extend_from_slice(target);
+                    target.copy_from_slice(&current);
+                    std::mem::swap(&mut current, &mut spare);
+                } else {
+                    if slot + block > n {
+                        overflow.lock().unwrap().extend_from_slice(&current);
+                    } else {
+                        unsafe {block_at(data, slot, block)}
+                            .copy_from_slice(&current);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Returns block at position `pos`.
+///
+/// Safety: block must be within the slice and not used by other threads.
+#[allow(clippy::mut_from_ref)]
+unsafe fn block_at<T>(data: &Shared<T>, pos: usize, block: usize)
+-> &mut [T] {
+    std::slice::from_raw_parts_mut(data.0.add(pos), block)
+}
+
+/// Elements of a bucket which are not in its range after the permutation.
+struct Leftovers<T> {
+    /// Parts of blocks written past the end of the bucket.
+    spilled: Vec<T>,
+    /// Range of the bucket, which holds its full blocks.
+    kept: (usize, usize),
+}
+
+/// Saves parts of blocks written past the ends of buckets, so the ranges
+/// they occupy can be overwritten.
+fn collect_leftovers<T: Copy>(input: &[T], layout: &Layout, block: usize,
+pointers: &[Mutex<Pointers>], overflow: &[T]) -> Vec<Leftovers<T>> {
+    let n = input.len();
+    let overflow_slot = if overflow.is_empty() {n} else {n - n % block};
+
+    pointers.iter().enumerate().map(|(b, p)| {
+        let written = p.lock().unwrap().write;
+        let end = layout.bounds[b + 1];
+        let delimiter = layout.delimiters[b];
+        let written = max(min(written, overflow_slot), delimiter);
+        let mut spilled = Vec::new();
+        if written > max(delimiter, end) {
+            spilled.extend_from_slice(&input[max(delimiter, end)..written]);
+        }
+        if layout.delimiters[b] <= overflow_slot
+            && overflow_slot < layout.delimiters[b + 1]
+            && !overflow.is_empty() {
+            spilled.extend_from_slice(overflow);
+        }
+        Leftovers {
+            spilled,
+            kept: (min(delimiter, end), min(written, end)),
+        }
+    }).collect()
+}
+
+/// Moves leftovers and buffered elements of bucket `b` to the free parts of
+/// its range.
+fn fill_bucket<T: Copy>(data: &Shared<T>, layout: &Layout, b: usize,
+leftovers: &Leftovers<T>, stripes: &[Stripe<T>], block: usize) {
+    let (start, end) = (layout.bounds[b], layout.bounds[b + 1]);
+    let (kept_start, kept_end) = leftovers.kept;
+    let holes = (start..kept_start).chain(kept_end..end);
+    let buffered = stripes.iter()
+        .flat_map(|s| s.buffers[b * block..b * block + s.lens[b]].iter());
+    debug_assert_eq!(end - start - (kept_end - kept_start),
+        leftovers.spilled.len()
+        + stripes.iter().map(|s| s.lens[b]).sum::<usize>());
+
+    for (pos, item) in holes.zip(leftovers.spilled.iter().chain(buffered)) {
+        // Safety: ranges of buckets are disjoint
+        unsafe {*data.0.add(pos) = *item;}
+    }
+}
+
+/// Distributes elements into buckets, returns bounds of the buckets.
+fn partition<T: PartialOrd + Copy>(input: &mut [T]) -> Vec<usize> {
+    let n = input.len();
+    let classifier = Classifier::new(input);
+    let block = block_len::<T>(n, classifier.buckets());
+    let stripes = [classify_stripe(input, 0, &classifier, block)];
+
+    let layout = Layout::new(&stripes, block);
+    let pointers = layout.pointers(stripes[0].end);
+    let overflow = Mutex::new(Vec::new());
+    let data = Shared(input.as_mut_ptr());
+    permute_blocks(&data, n, &classifier, block, &pointers, &overflow, 0);
+
+    let overflow = overflow.into_inner().unwrap();
+    let leftovers = collect_leftovers(input, &layout, block, &pointers,
+        &overflow);
+    let data = Shared(input.as_mut_ptr());
+    for (b, bucket) in leftovers.iter().enumerate() {
+        fill_bucket(&data, &layout, b, bucket, &stripes, block);
+    }
+    layout.bounds
+}
+
+/// Distributes elements into buckets on `threads` threads, returns bounds of
+/// the buckets.
+#[cfg(feature = "parallel")]
+pub(crate) fn par_partition<T: PartialOrd + Copy + Send + Sync>(
+input: &mut [T], threads: usize) -> Vec<usize> {
+    let n = input.len();
+    let classifier = Classifier::new(input);
+    let buckets = classifier.buckets();
+    let block = block_len::<T>(n / threads, buckets);
+    let stripe_len = n.div_ceil(threads).next_multiple_of(block);
+
+    let classifier = &classifier;
+    let stripes: Vec<Stripe<T>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = input.chunks_mut(stripe_len).enumerate()
+            .map(|(s, stripe)| scope.spawn(move ||
+                classify_stripe(stripe, s * stripe_len, classifier, block)))
+            .collect();
+        handles.into_iter().map(|h| h.join()
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+    });
+    let full = compact_blocks(input, &stripes, stripe_len, block);
+
+    let layout = Layout::new(&stripes, block);
+    let pointers = layout.pointers(full);
+    let overflow = Mutex::new(Vec::new());
+    let data = Shared(input.as_mut_ptr());
+    let threads = stripes.len();
+    std::thread::scope(|scope| {
+        for t in 0..threads {
+            let (data, pointers, overflow) = (&data, &pointers, &overflow);
+            scope.spawn(move || permute_blocks(data, n, classifier, block,
+                pointers, overflow, t * buckets / threads));
+        }
+    });
+
+    let overflow = overflow.into_inner().unwrap();
+    let leftovers = collect_leftovers(input, &layout, block, &pointers,
+        &overflow);
+    let data = Shared(input.as_mut_ptr());
+    std::thread::scope(|scope| {
+        for t in 0..threads {
+            let (data, layout, leftovers, stripes) =
+                (&data, &layout, &leftovers, &stripes);
+            scope.spawn(move || {
+                for b in (t..leftovers.len()).step_by(threads) {
+                    fill_bucket(data, layout, b, &leftovers[b], stripes,
+                        block);
+                }
+            });
+        }
+    });
+    layout.bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(input: Vec<i64>) {
+        let mut expected = input.clone();
+        expected.sort();
+        let mut vector_in = input;
+        sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, expected);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut vector_in = vec![10, 20, 11, 24, 15];
+        sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 15, 20, 24]);
+    }
+    #[test]
+    fn test_sample_patterns() {
+        for n in [4097, 10_000, 65_536, 100_003].iter() {
+            check((0..*n).map(|x| (x * 7919) % n).collect());
+            check((0..*n).collect());
+            check((0..*n).rev().collect());
+            check((0..*n).map(|x| x % 7).collect());
+            check((0..*n).map(|x| x * x % 1000 - 500).collect());
+        }
+        check(vec![3; 10_000]);
+    }
+    #[test]
+    fn test_sample_partition() {
+        let mut vector_in: Vec<i64> = (0..50_000).map(|x| (x * 7919) % 1000)
+            .collect();
+        let bounds = partition(&mut vector_in);
+        debug_assert_eq!(bounds[bounds.len() - 1], vector_in.len());
+        for b in 0..bounds.len() - 1 {
+            let bucket = &vector_in[bounds[b]..bounds[b + 1]];
+            let rest = &vector_in[bounds[b + 1]..];
+            debug_assert!(bucket.iter()
+                .all(|x| rest.iter().all(|y| x <= y)));
+            if b % 2 == 1 && !bucket.is_empty() {
+                debug_assert!(bucket.iter().all(|x| *x == bucket[0]));
+            }
+        }
+    }
+    #[test]
+    fn test_classifier() {
+        let mut sample: Vec<i64> = (0..10_000).map(|x| x % 3).collect();
+        let classifier = Classifier::new(&mut sample);
+        debug_assert_eq!(classifier.splitters, vec![0, 1, 2]);
+        let buckets: Vec<usize> = [-1, 0, 1, 2, 3].iter()
+            .map(|x| classifier.classify(x)).collect();
+        debug_assert_eq!(buckets, vec![0, 1, 3, 5, 6]);
+    }
+    #[test]
+    fn test_sample_floats() {
+        let mut vector_in: Vec<f64> = (0..20_000)
+            .map(|x| ((x * 7919) % 20_011) as f64 / 7.0 - 1000.0).collect();
+        let mut expected = vector_in.clone();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, expected);
+
+        // NaNs go to one bucket, which mustn't be partitioned again
+        let mut vector_in = vec![f64::NAN; 20_000];
+        sample_sort(&mut vector_in);
+        debug_assert!(vector_in.iter().all(|x| x.is_nan()));
+    }
+    #[test]
+    fn test_sample_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_sample_len1() {
+        let mut vector_in = vec![1];
+        sample_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::pivot::{PivotChooser, PivotStrategy};
+
+/// Finds the `n`-th smallest element of a slice in-place using
+/// [Quickselect](https://en.wikipedia.org/wiki/Quickselect) with
+/// [Median of medians](https://en.wikipedia.org/wiki/Median_of_medians)
+/// fallback.
+/// All kinds of slices can be used as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+/// Median and quantiles additionally need [`Copy`](https://doc.rust-lang.org/std/marker/trait.Copy.html)
+///
+/// Quickselect is the selection counterpart of quicksort: after partitioning
+/// only the side containing the wanted position is processed further, which
+/// gives linear time on average. Quickselect reuses the Lomuto partition scheme
+/// of quicksort, so sorted or reversed input makes it quadratic. To prevent
+/// this, the search checks that the processed range halves at least every two
+/// partitions, otherwise it switches to median of medians pivots, which
+/// guarantee linear time in the worst case.
+///
+/// After the call element at index `n` is the one which would be there if the
+/// slice was sorted, all elements before it are not greater and all elements
+/// after it are not less than it.
+///
+/// `select_nth` pivots on the last element like `quick_sort`, `select_nth_with`
+/// takes a [`PivotStrategy`](crate::PivotStrategy) for quickselect rounds.
+///
+/// Median of a slice with even length is the lower of two middle elements.
+/// Quantiles use the nearest-rank method, so every returned value is an
+/// element of the input.
+///
+/// # Panics
+/// `select_nth` and `select_nth_with` panic if `n` is out of bounds.
+/// `quantiles` of a non-empty slice panics if any of `q` is outside of
+/// `[0, 1]` or NaN.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5, 2, 7, 3, 9];
+/// assert_eq!(*sorting_rs::select_nth(&mut vec, 1), 3);
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// assert_eq!(*sorting_rs::select_nth(&mut strings, 2), "rustup");
+/// ```
+/// ```rust
+/// use sorting_rs::PivotStrategy;
+///
+/// let mut vec: Vec<i32> = (0..1000).collect();
+/// let nth = sorting_rs::select_nth_with(&mut vec, 10, PivotStrategy::First);
+/// assert_eq!(*nth, 10);
+/// ```
+/// ```rust
+/// let mut vec = vec![5, 2, 7, 3, 9];
+/// assert_eq!(sorting_rs::median(&mut vec), Some(5));
+/// ```
+/// ```rust
+/// let mut latency: Vec<u32> = (1..=100).rev().collect();
+/// let q = sorting_rs::quantiles(&mut latency, &[0.5, 0.99]);
+/// assert_eq!(q, &[50, 99]);
+/// ```
+pub fn select_nth<T: PartialOrd>(input: &mut [T], n: usize) -> &T {
+    select_nth_with(input, n, PivotStrategy::Last)
+}
+
+pub fn select_nth_with<T: PartialOrd>(input: &mut [T], n: usize,
+strategy: PivotStrategy) -> &T {
+    assert!(n < input.len(), "Index is out of bounds");
+
+    select_nth_range(input, n, &mut PivotChooser::new(strategy), false);
+    &input[n]
+}
+
+pub fn median<T: PartialOrd + Copy>(input: &mut [T]) -> Option<T> {
+    if input.is_empty() {return None;}
+
+    let mid = (input.len() - 1) / 2;
+    Some(*select_nth(input, mid))
+}
+
+pub fn quantiles<T: PartialOrd + Copy>(input: &mut [T], q: &[f64]) -> Vec<T> {
+    if input.is_empty() {return Vec::new();}
+
+    let len = input.len();
+    let mut ranks: Vec<(usize, usize)> = q.iter().enumerate().map(|(i, &q)| {
+        assert!((0.0..=1.0).contains(&q), "Quantile is out of range [0, 1]");
+        let rank = (q * len as f64).ceil() as usize;
+        (rank.clamp(1, len) - 1, i)
+    }).collect();
+    ranks.sort_unstable();
+
+    // Each selected rank splits the slice, so the next one is searched only
+    // among the elements to its right
+    let mut result = vec![input[0]; q.len()];
+    let mut start = 0;
+    let mut chooser = PivotChooser::new(PivotStrategy::Last);
+    for (rank, i) in ranks {
+        select_nth_range(&mut input[start..], rank - start, &mut chooser,
+            false);
+        result[i] = input[rank];
+        start = rank;
+    }
+    result
+}
+
+fn select_nth_range<T: PartialOrd>(input: &mut [T], n: usize,
+chooser: &mut PivotChooser, mut fallback: bool) {
+    let mut input = input;
+    let mut n = n;
+    let mut checkpoint = input.len();
+    let mut rounds = 0;
+
+    loop {
+        if input.len() <= 10 {
+            crate::small_sort::small_sort(input);
+            return;
+        }
+
+        let (lt, gt) = if fallback {
+            let pivot = median_of_medians(input, chooser);
+            crate::partition_3way(input, pivot)
+        } else {
+            let pivot = chooser.choose(input);
+            input.swap(pivot, input.len() - 1);
+            let pivot = crate::quick_sort::lomuto_partition(input);
+            (pivot, pivot + 1)
+        };
+
+        if n < lt {
+            input = &mut input[..lt];
+        } else if n >= gt {
+            input = &mut input[gt..];
+            n -= gt;
+        } else {
+            return;
+        }
+
+        rounds += 1;
+        if rounds == 2 {
+            if input.len() > checkpoint / 2 {fallback = true;}
+            checkpoint = input.len();
+            rounds = 0;
+        }
+    }
+}
+
+/// Returns index of an approximate median, which is guaranteed to be greater
+/// than 30% and less than 30% of elements.
+fn median_of_medians<T: PartialOrd>(input: &mut [T],
+chooser: &mut PivotChooser) -> usize {
+    let len = input.len();
+    if len <= 5 {
+        crate::small_sort::small_sort(input);
+        return len / 2;
+    }
+
+    // Medians of groups of five are moved to the front of the slice
+    let groups = len.div_ceil(5);
+    for group in 0..groups {
+        let start = group * 5;
+        let end = std::cmp::min(start + 5, len);
+        crate::small_sort::small_sort(&mut input[start..end]);
+        input.swap(group, start + (end - start) / 2);
+    }
+
+    select_nth_range(&mut input[..groups], groups / 2, chooser, true);
+    groups / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_selected(input: &[i32], n: usize) {
+        debug_assert!(input[..n].iter().all(|x| *x <= input[n]));
+        debug_assert!(input[n + 1..].iter().all(|x| *x >= input[n]));
+    }
+
+    #[test]
+    fn test_select_nth() {
+        let mut vector_in = vec![10, 20, 11, 24, 13];
+        debug_assert_eq!(*select_nth(&mut vector_in, 2), 13);
+        check_selected(&vector_in, 2);
+    }
+    #[test]
+    fn test_select_nth_every_index() {
+        let base: Vec<i32> = (0..100).map(|x| (x * 37) % 101).collect();
+        let mut sorted = base.clone();
+        sorted.sort();
+        for (n, expected) in sorted.iter().enumerate() {
+            let mut vector_in = base.clone();
+            debug_assert_eq!(select_nth(&mut vector_in, n), expected);
+            check_selected(&vector_in, n);
+        }
+    }
+    #[test]
+    fn test_select_nth_sorted_and_reversed() {
+        let mut vector_in: Vec<i32> = (0..10_000).collect();
+        debug_assert_eq!(*select_nth(&mut vector_in, 9000), 9000);
+        let mut vector_in: Vec<i32> = (0..10_000).rev().collect();
+        debug_assert_eq!(*select_nth(&mut vector_in, 10), 10);
+    }
+    #[test]
+    fn test_select_nth_duplicates() {
+        let mut vector_in = vec![7; 10_000];
+        vector_in[5000] = 1;
+        debug_assert_eq!(*select_nth(&mut vector_in, 9000), 7);
+        debug_assert_eq!(*select_nth(&mut vector_in, 0), 1);
+    }
+    #[test]
+    fn test_median_of_medians() {
+        let mut vector_in: Vec<i32> = (0..1000).rev().collect();
+        let mut chooser = PivotChooser::new(PivotStrategy::Last);
+        select_nth_range(&mut vector_in, 400, &mut chooser, true);
+        debug_assert_eq!(vector_in[400], 400);
+        check_selected(&vector_in, 400);
+    }
+    #[test]
+    fn test_select_nth_with() {
+        let strategies = [
+            PivotStrategy::First, PivotStrategy::Middle,
+            PivotStrategy::MedianOfThree, PivotStrategy::Ninther,
+            PivotStrategy::Random(42),
+        ];
+        for strategy in strategies.iter() {
+            let mut vector_in: Vec<i32> = (0..1000).rev().collect();
+            debug_assert_eq!(*select_nth_with(&mut vector_in, 300, *strategy),
+                300);
+            check_selected(&vector_in, 300);
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn test_select_nth_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        select_nth(&mut vector_in, 0);
+    }
+    #[test]
+    fn test_median() {
+        debug_assert_eq!(median(&mut [4, 1, 3, 2]), Some(2));
+        debug_assert_eq!(median(&mut [4.5, 1.5, 3.5]), Some(3.5));
+        debug_assert_eq!(median::<i32>(&mut []), None);
+    }
+    #[test]
+    fn test_quantiles() {
+        let mut vector_in: Vec<u32> = (1..=1000).rev().collect();
+        let q = quantiles(&mut vector_in, &[0.99, 0.5, 0.0, 1.0, 0.999]);
+        debug_assert_eq!(q, vec![990, 500, 1, 1000, 999]);
+    }
+    #[test]
+    fn test_quantiles_empty() {
+        let mut vector_in: Vec<u32> = vec![];
+        debug_assert_eq!(quantiles(&mut vector_in, &[0.5]), vec![]);
+    }
+    #[test]
+    #[should_panic]
+    fn test_quantiles_nan() {
+        quantiles(&mut [1, 2, 3], &[0.5, f64::NAN]);
+    }
+    #[test]
+    #[should_panic]
+    fn test_quantiles_out_of_range() {
+        quantiles(&mut [1, 2, 3], &[1.5]);
+    }
+}
@@ -25,7 +25,6 @@
 /// sorting_rs::selection_double_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn selection_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -34,9 +33,9 @@ pub fn selection_sort<T: PartialOrd>(input: &mut [T]) {
             let mut min = &input[i];
             let mut index_min = i;
             
-            for j in i + 1..input.len() {
-                if input[j] < *min {
-                    min = &input[j];
+            for (j, item) in input.iter().enumerate().skip(i + 1) {
+                if item < min {
+                    min = item;
                     index_min = j;
                 }
             }
@@ -1,3 +1,6 @@
+use crate::insertion_sort::insert_tail;
+use std::convert::TryFrom;
+
 /// Sorts a slice in-place using
 /// [Shell sort](https://en.wikipedia.org/wiki/Shellsort).
 /// All kinds of slices can be sorted as long as they implement
@@ -7,8 +10,28 @@
 /// items that are far apart. The idea is to arrange the list of elements so
 /// that, starting anywhere, taking every hth element produces a sorted list.
 /// Such a list is said to be h-sorted. It can also be thought of as h
-/// interleaved lists, each individually sorted.
+/// interleaved lists, each individually sorted. Like in `insertion_sort`,
+/// elements are moved into a hole instead of being swapped.
 /// 
+/// The efficiency of shellsort depends on the gap sequence. Shell's original
+/// sequence `n/2`<sup>`k`</sup> is quadratic in the worst case, that's why
+/// `shell_sort` uses the sequence found by Marcin Ciura, which is the fastest
+/// one known in practice. Other sequences can be used with `shell_sort_with`,
+/// which takes any type implementing [`GapSequence`] trait:
+///
+/// | Sequence    | Gaps                                         | Worst-case              |
+/// | ----------- | -------------------------------------------- | ----------------------- |
+/// | `Shell`     | `n/2, n/4, ..., 1`                           | `n`<sup>`2`</sup>       |
+/// | `Hibbard`   | `1, 3, 7, 15, 31, ...`                       | `n`<sup>`3/2`</sup>     |
+/// | `Knuth`     | `1, 4, 13, 40, 121, ...`                     | `n`<sup>`3/2`</sup>     |
+/// | `Sedgewick` | `1, 8, 23, 77, 281, ...`                     | `n`<sup>`4/3`</sup>     |
+/// | `Pratt`     | `1, 2, 3, 4, 6, 8, 9, 12, ...`               | `nlog`<sup>`2`</sup>`n` |
+/// | `Tokuda`    | `1, 4, 9, 20, 46, 103, ...`                  | unknown                 |
+/// | `Ciura`     | `1, 4, 10, 23, 57, 132, 301, 701, 1750, ...` | unknown                 |
+///
+/// User-defined sequences can be passed as a slice of increasing gaps starting
+/// with `1`, or by implementing [`GapSequence`] trait.
+///
 /// # Examples
 /// ```rust
 /// let mut vec = vec![0, -1, -2, -3,];
@@ -20,47 +43,141 @@
 /// sorting_rs::shell_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
+/// ```rust
+/// use sorting_rs::shell_sort::Pratt;
+///
+/// let mut vec = vec![0, -1, -2, -3,];
+/// sorting_rs::shell_sort_with(&mut vec, &Pratt);
+/// assert_eq!(vec, &[-3, -2, -1, 0]);
+/// ```
+/// ```rust
+/// let mut vec = vec![0, -1, -2, -3, 5, 4];
+/// sorting_rs::shell_sort_with(&mut vec, &[1, 3, 5]);
+/// assert_eq!(vec, &[-3, -2, -1, 0, 4, 5]);
+/// ```
+pub fn shell_sort<T: PartialOrd>(input: &mut [T]) {
+    shell_sort_with(input, &Ciura);
+}
+
+pub fn shell_sort_with<T: PartialOrd, G: GapSequence + ?Sized>(input: &mut [T],
+gaps: &G) {
+    if input.len() < 2 {return;}
 
-struct GapSequence {
-    gap: usize,
+    let len = input.len();
+    assert_eq!(gaps.gap(0, len), Some(1), "Gap sequence must start with 1");
+
+    let mut count = 1;
+    while let Some(gap) = gaps.gap(count, len) {
+        if gap >= len {break;}
+        count += 1;
+    }
+
+    for k in (0..count).rev() {
+        let gap = gaps.gap(k, len).unwrap();
+        for i in gap..len {
+            insert_tail(input, i, gap);
+        }
+    }
+}
+
+/// Sequence of gaps for [`shell_sort_with`].
+pub trait GapSequence {
+    /// Returns `k`-th gap in increasing order for sorting `len` elements,
+    /// `None` if there are no more gaps or the gap overflows `usize`.
+    /// The first gap must be `1`, gaps which are not less than `len` are not
+    /// used.
+    fn gap(&self, k: usize, len: usize) -> Option<usize>;
 }
 
-impl GapSequence {
-    fn new(n: usize) -> Self {
-        Self { gap: n }
+/// Shell's original gaps `n/2`<sup>`k`</sup>.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shell;
+
+/// Hibbard gaps 2<sup>`k`</sup>` - 1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hibbard;
+
+/// Knuth gaps (3<sup>`k`</sup>` - 1) / 2`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Knuth;
+
+/// Sedgewick (1986) gaps 4<sup>`k`</sup>` + 3*2`<sup>`k-1`</sup>` + 1`
+/// prefixed with `1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sedgewick;
+
+/// Pratt gaps, 3-smooth numbers 2<sup>`p`</sup>3<sup>`q`</sup>.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pratt;
+
+/// Tokuda gaps `ceil(h`<sub>`k`</sub>`)`, where
+/// `h`<sub>`k`</sub>` = 2.25h`<sub>`k-1`</sub>` + 1`, `h`<sub>`1`</sub>` = 1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tokuda;
+
+/// Ciura gaps, extended by multiplying by 2.25.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ciura;
+
+impl GapSequence for Shell {
+    fn gap(&self, k: usize, len: usize) -> Option<usize> {
+        let bits = (usize::BITS - 1 - len.max(2).leading_zeros()) as usize;
+        if k >= bits {return None;}
+        Some(len >> (bits - k))
     }
 }
 
-impl Iterator for GapSequence {
-    type Item = usize;
+impl GapSequence for Hibbard {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        let k = u32::try_from(k).ok()?;
+        2usize.checked_pow(k + 1).map(|x| x - 1)
+    }
+}
 
-    fn next(&mut self) -> Option<usize> {
-        self.gap /= 2;
+impl GapSequence for Knuth {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        let k = u32::try_from(k).ok()?;
+        3usize.checked_pow(k + 1).map(|x| (x - 1) / 2)
+    }
+}
 
-        if self.gap > 0 {
-            Some(self.gap)
-        } else {
-            None
-        }
+impl GapSequence for Sedgewick {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        if k == 0 {return Some(1);}
+        let k = u32::try_from(k).ok()?;
+        4usize.checked_pow(k)?
+            .checked_add(3 * 2usize.checked_pow(k - 1)?)?
+            .checked_add(1)
     }
 }
 
-pub fn shell_sort<T: PartialOrd>(input: &mut [T]) {
-    if input.len() < 2 {return;}
-    
-    let len = input.len();
-    let gaps = GapSequence::new(len);
+impl GapSequence for Pratt {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        crate::PRATT_GAPS.get(k).copied()
+    }
+}
 
-    for gap in gaps {
-        for i in gap..len {
-            let mut j = i;
+impl GapSequence for Tokuda {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        crate::TOKUDA_GAPS.get(k).copied()
+    }
+}
+
+impl GapSequence for Ciura {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        crate::CIURA_GAPS.get(k).copied()
+    }
+}
 
-            while j >= gap && input[j - gap] > input[j] {
-                input.swap(j - gap, j);
+impl GapSequence for [usize] {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        self.get(k).copied()
+    }
+}
 
-                j -= gap;
-            }
-        }
+impl<const N: usize> GapSequence for [usize; N] {
+    fn gap(&self, k: usize, _len: usize) -> Option<usize> {
+        self.get(k).copied()
     }
 }
 
@@ -68,12 +185,57 @@ pub fn shell_sort<T: PartialOrd>(input: &mut [T]) {
 mod test {
     use super::*;
 
+    fn collect_gaps<G: GapSequence>(gaps: &G, len: usize) -> Vec<usize> {
+        (0..).map(|k| gaps.gap(k, len)).take_while(|g| g.is_some())
+            .map(|g| g.unwrap()).take_while(|g| *g < len).collect()
+    }
+
     #[test]
     fn test_correct_gap_sequence() {
-        let gaps: Vec<_> = GapSequence::new(10).collect();
+        let gaps: Vec<_> = collect_gaps(&Shell, 10).into_iter().rev()
+            .collect();
         debug_assert_eq!(gaps, &[5, 2, 1]);
     }
     #[test]
+    fn test_gap_sequences() {
+        debug_assert_eq!(collect_gaps(&Hibbard, 20), &[1, 3, 7, 15]);
+        debug_assert_eq!(collect_gaps(&Knuth, 50), &[1, 4, 13, 40]);
+        debug_assert_eq!(collect_gaps(&Sedgewick, 300), &[1, 8, 23, 77, 281]);
+        debug_assert_eq!(collect_gaps(&Pratt, 20),
+            &[1, 2, 3, 4, 6, 8, 9, 12, 16, 18]);
+        debug_assert_eq!(collect_gaps(&Tokuda, 60), &[1, 4, 9, 20, 46]);
+        debug_assert_eq!(collect_gaps(&Ciura, 60), &[1, 4, 10, 23, 57]);
+    }
+    #[test]
+    fn test_gap_sequences_overflow() {
+        debug_assert_eq!(Hibbard.gap(200, usize::MAX), None);
+        debug_assert_eq!(Knuth.gap(200, usize::MAX), None);
+        debug_assert_eq!(Sedgewick.gap(200, usize::MAX), None);
+        debug_assert_eq!(Pratt.gap(5000, usize::MAX), None);
+        debug_assert!(collect_gaps(&Pratt, usize::MAX).windows(2)
+            .all(|w| w[0] < w[1]));
+    }
+    #[test]
+    fn test_shell_with() {
+        let reference: Vec<i32> = (0..1000).collect();
+        let input: Vec<i32> = (0..1000).map(|x| (x * 367) % 1000).collect();
+        let sequences: [&dyn GapSequence; 8] = [
+            &Shell, &Hibbard, &Knuth, &Sedgewick, &Pratt, &Tokuda, &Ciura,
+            &[1, 5, 19, 41, 109],
+        ];
+        for gaps in sequences.iter() {
+            let mut vector_in = input.clone();
+            shell_sort_with(&mut vector_in, *gaps);
+            debug_assert_eq!(vector_in, reference);
+        }
+    }
+    #[test]
+    #[should_panic]
+    fn test_shell_with_bad_sequence() {
+        let mut vector_in = vec![3, 2, 1];
+        shell_sort_with(&mut vector_in, &[2, 4]);
+    }
+    #[test]
     fn test_shell() {
         let mut vector_in = vec![11, 20, 21, 40, 11, 60, 5];
         shell_sort(&mut vector_in);
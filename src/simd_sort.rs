@@ -0,0 +1,743 @@
+/// Sorts a slice of primitive numbers in-place using quicksort with
+/// vectorised partitioning and
+/// [Bitonic sort](https://en.wikipedia.org/wiki/Bitonic_sorter) kernels on
+/// SIMD registers.
+/// Slices of `i32`, `u32`, `f32`, `i64`, `u64` and `f64` can be sorted.
+///
+/// Comparators of a sorting network don't depend on data, so a whole vector
+/// of them is executed with one minimum and one maximum instruction. Blocks
+/// of up to eight registers, 32 or 64 elements, are padded to a power of two,
+/// loaded into registers and sorted by a bitonic network, which is unrolled
+/// for every number of registers: comparators between different registers
+/// are vertical, the ones inside a register are done after permuting it
+/// against itself. Larger ranges are partitioned around median of three or
+/// ninther a vector at a time: elements greater than the pivot are found by
+/// one comparison, the vector is permuted with a lookup table to group them
+/// at its end and written to both ends of the range. If all elements of the
+/// range are not greater than the pivot, the ones equal to it are separated
+/// and left in place, so duplicates don't slow the sort down. As in
+/// `intro_sort`, ranges are sorted by heapsort when recursion gets too deep.
+///
+/// Unsigned numbers and floats are sorted as signed integers of the same
+/// width with some bits flipped, and restored afterwards. Floats are sorted in
+/// [total order](https://doc.rust-lang.org/std/primitive.f64.html#method.total_cmp):
+/// `-0.0` goes before `0.0`, and NaNs go to the ends depending on their sign.
+///
+/// The instructions are picked at run time: AVX2 is used for 32-bit and
+/// 64-bit numbers, SSE4.1 for 32-bit ones and SSE4.2, which adds comparison
+/// of 64-bit lanes, for 64-bit ones. On other processors and architectures
+/// numbers are sorted with `intro_sort`.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![5, 3, 2, 4];
+/// sorting_rs::simd_sort(&mut vec);
+/// assert_eq!(vec, &[2, 3, 4, 5]);
+/// ```
+/// ```rust
+/// let mut vec = vec![0.5, -1.0, 3.25, -0.0, 0.0];
+/// sorting_rs::simd_sort(&mut vec);
+/// assert_eq!(vec, &[-1.0, -0.0, 0.0, 0.5, 3.25]);
+/// ```
+/// ```rust
+/// let mut vec: Vec<u64> = (0..100_000).map(|x| x * 7919 % 100_003).collect();
+/// sorting_rs::simd_sort(&mut vec);
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn simd_sort<T: SimdElement>(input: &mut [T]) {
+    T::sort_with(input, sealed::Isa::detect());
+}
+
+/// Numbers, which can be sorted by `simd_sort`.
+pub trait SimdElement: Copy + PartialOrd + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed: Sized {
+        fn sort_with(input: &mut [Self], isa: Isa);
+    }
+
+    /// Instruction sets, for which `simd_sort` has kernels.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Isa {
+        Avx2,
+        Sse42,
+        Sse41,
+        Portable,
+    }
+
+    impl Isa {
+        pub fn detect() -> Self {
+            #[cfg(target_arch = "x86_64")]
+            {
+                if is_x86_feature_detected!("avx2") {return Isa::Avx2;}
+                if is_x86_feature_detected!("sse4.2") {return Isa::Sse42;}
+                if is_x86_feature_detected!("sse4.1") {return Isa::Sse41;}
+            }
+            Isa::Portable
+        }
+    }
+}
+
+use self::sealed::Isa;
+
+macro_rules! simd_element {
+    ($($t:ident => $key:ident $sort:ident $map:expr),*) => {$(
+        impl sealed::Sealed for $t {
+            fn sort_with(input: &mut [Self], isa: Isa) {
+                // Keys have the same size, and every bit pattern is valid
+                let keys = unsafe {std::slice::from_raw_parts_mut(
+                    input.as_mut_ptr() as *mut $key, input.len())};
+                // Mapping to keys is its own inverse
+                let map: fn($key) -> $key = $map;
+                keys.iter_mut().for_each(|key| *key = map(*key));
+                $sort(keys, isa);
+                keys.iter_mut().for_each(|key| *key = map(*key));
+            }
+        }
+
+        impl SimdElement for $t {}
+    )*};
+}
+
+simd_element!(
+    i32 => i32 sort_i32 |key| key,
+    u32 => i32 sort_i32 |key| key ^ i32::MIN,
+    f32 => i32 sort_i32 |key| key ^ ((key >> 31) as u32 >> 1) as i32,
+    i64 => i64 sort_i64 |key| key,
+    u64 => i64 sort_i64 |key| key ^ i64::MIN,
+    f64 => i64 sort_i64 |key| key ^ ((key >> 63) as u64 >> 1) as i64
+);
+
+fn sort_i32(keys: &mut [i32], isa: Isa) {
+    match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe {kernels::sort_i32_avx2(keys)},
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse42 | Isa::Sse41 => unsafe {kernels::sort_i32_sse41(keys)},
+        _ => crate::intro_sort(keys),
+    }
+}
+
+fn sort_i64(keys: &mut [i64], isa: Isa) {
+    match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe {kernels::sort_i64_avx2(keys)},
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse42 => unsafe {kernels::sort_i64_sse42(keys)},
+        _ => crate::intro_sort(keys),
+    }
+}
+
+/// Vectorised quicksort and bitonic kernels, which exist only for x86_64.
+#[cfg(target_arch = "x86_64")]
+mod kernels {
+    use crate::intro_sort::depth_limit;
+    use crate::pivot::{PivotChooser, PivotStrategy};
+    use std::arch::x86_64::*;
+
+    /// Ranges up to this number of vectors are sorted by a bitonic kernel.
+    const BLOCK_VECTORS: usize = 8;
+    /// The longest block of any kernel.
+    const BLOCK_LEN: usize = BLOCK_VECTORS * MAX_LANES;
+    /// The widest vector has this number of lanes.
+    const MAX_LANES: usize = 8;
+
+    /// Operations on a SIMD register of integers. All of them require the
+    /// instruction set of the implementation.
+    trait Vector: Copy {
+        type Elem: Copy + Ord;
+        /// Register with lane indices or masks.
+        type Index: Copy;
+        const LANES: usize;
+        const MAX: Self::Elem;
+
+        unsafe fn splat(item: Self::Elem) -> Self;
+        unsafe fn load(ptr: *const Self::Elem) -> Self;
+        unsafe fn store(self, ptr: *mut Self::Elem);
+        unsafe fn min(self, other: Self) -> Self;
+        unsafe fn max(self, other: Self) -> Self;
+        /// Returns the permutation, which moves lane `j ^ m` to lane `j`.
+        unsafe fn xor_index(m: usize) -> Self::Index;
+        /// Returns the mask of lanes, which have the highest bit of `m` set.
+        unsafe fn upper_index(m: usize) -> Self::Index;
+        unsafe fn permute(self, index: Self::Index) -> Self;
+        /// Takes lanes of `other`, where `mask` is set.
+        unsafe fn blend(self, other: Self, mask: Self::Index) -> Self;
+        /// Returns the bit mask of lanes greater than the ones of `other`.
+        unsafe fn greater(self, other: Self) -> usize;
+        /// Moves lanes with set bits of `mask` after the others.
+        unsafe fn compress(self, mask: usize) -> Self;
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn sort_i32_avx2(keys: &mut [i32]) {
+        quick_sort::<Avx2I32>(keys);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn sort_i64_avx2(keys: &mut [i64]) {
+        quick_sort::<Avx2I64>(keys);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn sort_i32_sse41(keys: &mut [i32]) {
+        quick_sort::<Sse41I32>(keys);
+    }
+
+    #[target_feature(enable = "sse4.2")]
+    pub(super) unsafe fn sort_i64_sse42(keys: &mut [i64]) {
+        quick_sort::<Sse42I64>(keys);
+    }
+
+    /// Sorts ranges iteratively, so the vector operations are inlined into the
+    /// functions enabling the instruction set.
+    #[inline(always)]
+    unsafe fn quick_sort<V: Vector>(input: &mut [V::Elem]) {
+        if input.len() <= BLOCK_VECTORS * V::LANES {
+            block_sort::<V>(input);
+            return;
+        }
+
+        let mut stack = vec![(0, input.len(), depth_limit(input.len()))];
+        while let Some((start, end, limit)) = stack.pop() {
+            let range = &mut input[start..end];
+            if range.len() <= BLOCK_VECTORS * V::LANES {
+                block_sort::<V>(range);
+            } else if limit == 0 {
+                crate::heap_sort(range);
+            } else {
+                let strategy = if range.len() < 128 {
+                    PivotStrategy::MedianOfThree
+                } else {
+                    PivotStrategy::Ninther
+                };
+                let pivot = range[PivotChooser::new(strategy).choose(range)];
+                let mid = partition::<V>(range, pivot, false);
+                if mid < range.len() {
+                    stack.push((start + mid, end, limit - 1));
+                    stack.push((start, start + mid, limit - 1));
+                } else {
+                    // Nothing is greater than the pivot, so elements equal to
+                    // it are moved to the end, where they belong
+                    let mid = partition::<V>(range, pivot, true);
+                    stack.push((start, start + mid, limit - 1));
+                }
+            }
+        }
+    }
+
+    /// Moves elements greater than the pivot, or not less than it if
+    /// `equal_right` is set, to the end and returns the number of the others.
+    /// The slice must have at least two vectors of elements.
+    #[inline(always)]
+    unsafe fn partition<V: Vector>(input: &mut [V::Elem], pivot: V::Elem,
+    equal_right: bool) -> usize {
+        let lanes = V::LANES;
+        let ptr = input.as_mut_ptr();
+        let splat = V::splat(pivot);
+        let all = (1 << lanes) - 1;
+        let right = |v: V| if equal_right {!splat.greater(v) & all}
+            else {v.greater(splat)};
+
+        // The first and the last vectors are put aside, so there is always
+        // space for a whole vector at both ends: elements are read from the end
+        // with less space left
+        let mut rest = [V::MAX; 3 * MAX_LANES];
+        V::load(ptr).store(rest.as_mut_ptr());
+        V::load(ptr.add(input.len() - lanes))
+            .store(rest.as_mut_ptr().add(lanes));
+        let (mut read_left, mut read_right) = (lanes, input.len() - lanes);
+        let (mut write_left, mut write_right) = (0, input.len());
+        while read_right - read_left >= lanes {
+            let v = if read_left - write_left <= write_right - read_right {
+                read_left += lanes;
+                V::load(ptr.add(read_left - lanes))
+            } else {
+                read_right -= lanes;
+                V::load(ptr.add(read_right))
+            };
+            let mask = right(v);
+            let v = v.compress(mask);
+            v.store(ptr.add(write_left));
+            v.store(ptr.add(write_right - lanes));
+            let greater = mask.count_ones() as usize;
+            write_left += lanes - greater;
+            write_right -= greater;
+        }
+
+        let tail = read_right - read_left;
+        std::ptr::copy_nonoverlapping(ptr.add(read_left),
+            rest.as_mut_ptr().add(2 * lanes), tail);
+        // All elements left are in the buffer, so both ends can be written
+        for &item in &rest[..2 * lanes + tail] {
+            let right = if equal_right {item >= pivot} else {item > pivot};
+            *ptr.add(write_left) = item;
+            *ptr.add(write_right - 1) = item;
+            write_left += !right as usize;
+            write_right -= right as usize;
+        }
+        write_left
+    }
+
+    /// Sorts up to `BLOCK_VECTORS` vectors of elements padded with maximal
+    /// values to a power of two with a bitonic network on registers.
+    #[inline(always)]
+    unsafe fn block_sort<V: Vector>(input: &mut [V::Elem]) {
+        if input.len() < 2 {return;}
+        // The number of registers is constant in every kernel, so the network
+        // is unrolled and the registers aren't stored to memory between layers
+        match input.len().div_ceil(V::LANES) {
+            1 => block_sort_n::<V, 1>(input),
+            2 => block_sort_n::<V, 2>(input),
+            3..=4 => block_sort_n::<V, 4>(input),
+            _ => block_sort_n::<V, 8>(input),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn block_sort_n<V: Vector, const N: usize>(input: &mut [V::Elem]) {
+        let mut block = [V::MAX; BLOCK_LEN];
+        block[..input.len()].copy_from_slice(input);
+        let mut vectors = [V::splat(V::MAX); N];
+        for (i, v) in vectors.iter_mut().enumerate() {
+            *v = V::load(block.as_ptr().add(i * V::LANES));
+        }
+
+        // Every stage merges sorted halves of blocks of `size` elements
+        let mut size = 2;
+        while size <= N * V::LANES {
+            bitonic_layer(&mut vectors, size - 1);
+            let mut distance = size / 4;
+            while distance > 0 {
+                bitonic_layer(&mut vectors, distance);
+                distance /= 2;
+            }
+            size *= 2;
+        }
+
+        for (i, v) in vectors.iter().enumerate() {
+            v.store(block.as_mut_ptr().add(i * V::LANES));
+        }
+        input.copy_from_slice(&block[..input.len()]);
+    }
+
+    /// Compares and swaps every element `i` with element `i ^ m`.
+    #[inline(always)]
+    unsafe fn bitonic_layer<V: Vector>(vectors: &mut [V], m: usize) {
+        let lanes = V::LANES;
+        let inner = m & (lanes - 1);
+        let index = V::xor_index(inner);
+        if m < lanes {
+            let upper = V::upper_index(m);
+            for v in vectors.iter_mut() {
+                let other = v.permute(index);
+                *v = v.min(other).blend(v.max(other), upper);
+            }
+        } else {
+            let step = m / lanes;
+            for i in 0..vectors.len() {
+                let j = i ^ step;
+                if j < i {continue;}
+                let (a, mut b) = (vectors[i], vectors[j]);
+                if inner != 0 {b = b.permute(index);}
+                vectors[i] = a.min(b);
+                vectors[j] = a.max(b);
+                if inner != 0 {vectors[j] = vectors[j].permute(index);}
+            }
+        }
+    }
+
+    /// Returns tables of indices of `K / lanes` parts of every lane, which move
+    /// lanes with set bits of the mask after the others, for all masks.
+    const fn compress_table<const N: usize, const K: usize>(lanes: usize)
+    -> [[u8; K]; N] {
+        let unit = K / lanes;
+        let mut table = [[0; K]; N];
+        let mut mask = 0;
+        while mask < N {
+            let mut k = 0;
+            let mut bit = 0;
+            while bit < 2 {
+                let mut lane = 0;
+                while lane < lanes {
+                    if mask >> lane & 1 == bit {
+                        let mut part = 0;
+                        while part < unit {
+                            table[mask][k * unit + part] =
+                                (lane * unit + part) as u8;
+                            part += 1;
+                        }
+                        k += 1;
+                    }
+                    lane += 1;
+                }
+                bit += 1;
+            }
+            mask += 1;
+        }
+        table
+    }
+
+    static COMPRESS_8X32: [[u8; 8]; 256] = compress_table(8);
+    static COMPRESS_4X64: [[u8; 8]; 16] = compress_table(4);
+    static COMPRESS_4X32: [[u8; 16]; 16] = compress_table(4);
+    static COMPRESS_2X64: [[u8; 16]; 4] = compress_table(2);
+
+    #[derive(Clone, Copy)]
+    struct Avx2I32(__m256i);
+
+    impl Vector for Avx2I32 {
+        type Elem = i32;
+        type Index = __m256i;
+        const LANES: usize = 8;
+        const MAX: i32 = i32::MAX;
+
+        #[inline(always)]
+        unsafe fn splat(item: i32) -> Self {
+            Self(_mm256_set1_epi32(item))
+        }
+        #[inline(always)]
+        unsafe fn load(ptr: *const i32) -> Self {
+            Self(_mm256_loadu_si256(ptr as *const __m256i))
+        }
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut i32) {
+            _mm256_storeu_si256(ptr as *mut __m256i, self.0);
+        }
+        #[inline(always)]
+        unsafe fn min(self, other: Self) -> Self {
+            Self(_mm256_min_epi32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn max(self, other: Self) -> Self {
+            Self(_mm256_max_epi32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn xor_index(m: usize) -> __m256i {
+            _mm256_xor_si256(_mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+                _mm256_set1_epi32(m as i32))
+        }
+        #[inline(always)]
+        unsafe fn upper_index(m: usize) -> __m256i {
+            let bit = _mm256_set1_epi32(1 << m.ilog2());
+            let lanes = _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7);
+            _mm256_cmpeq_epi32(_mm256_and_si256(lanes, bit), bit)
+        }
+        #[inline(always)]
+        unsafe fn permute(self, index: __m256i) -> Self {
+            Self(_mm256_permutevar8x32_epi32(self.0, index))
+        }
+        #[inline(always)]
+        unsafe fn blend(self, other: Self, mask: __m256i) -> Self {
+            Self(_mm256_blendv_epi8(self.0, other.0, mask))
+        }
+        #[inline(always)]
+        unsafe fn greater(self, other: Self) -> usize {
+            _mm256_movemask_ps(_mm256_castsi256_ps(
+                _mm256_cmpgt_epi32(self.0, other.0))) as usize
+        }
+        #[inline(always)]
+        unsafe fn compress(self, mask: usize) -> Self {
+            let index = _mm_loadl_epi64(COMPRESS_8X32[mask].as_ptr()
+                as *const __m128i);
+            self.permute(_mm256_cvtepu8_epi32(index))
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Avx2I64(__m256i);
+
+    impl Vector for Avx2I64 {
+        type Elem = i64;
+        type Index = __m256i;
+        const LANES: usize = 4;
+        const MAX: i64 = i64::MAX;
+
+        #[inline(always)]
+        unsafe fn splat(item: i64) -> Self {
+            Self(_mm256_set1_epi64x(item))
+        }
+        #[inline(always)]
+        unsafe fn load(ptr: *const i64) -> Self {
+            Self(_mm256_loadu_si256(ptr as *const __m256i))
+        }
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut i64) {
+            _mm256_storeu_si256(ptr as *mut __m256i, self.0);
+        }
+        #[inline(always)]
+        unsafe fn min(self, other: Self) -> Self {
+            let greater = _mm256_cmpgt_epi64(self.0, other.0);
+            Self(_mm256_blendv_epi8(self.0, other.0, greater))
+        }
+        #[inline(always)]
+        unsafe fn max(self, other: Self) -> Self {
+            let greater = _mm256_cmpgt_epi64(self.0, other.0);
+            Self(_mm256_blendv_epi8(other.0, self.0, greater))
+        }
+        #[inline(always)]
+        unsafe fn xor_index(m: usize) -> __m256i {
+            // Lanes are moved as pairs of 32-bit halves
+            _mm256_xor_si256(_mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+                _mm256_set1_epi32(2 * m as i32))
+        }
+        #[inline(always)]
+        unsafe fn upper_index(m: usize) -> __m256i {
+            let bit = _mm256_set1_epi64x(1 << m.ilog2());
+            let lanes = _mm256_setr_epi64x(0, 1, 2, 3);
+            _mm256_cmpeq_epi64(_mm256_and_si256(lanes, bit), bit)
+        }
+        #[inline(always)]
+        unsafe fn permute(self, index: __m256i) -> Self {
+            Self(_mm256_permutevar8x32_epi32(self.0, index))
+        }
+        #[inline(always)]
+        unsafe fn blend(self, other: Self, mask: __m256i) -> Self {
+            Self(_mm256_blendv_epi8(self.0, other.0, mask))
+        }
+        #[inline(always)]
+        unsafe fn greater(self, other: Self) -> usize {
+            _mm256_movemask_pd(_mm256_castsi256_pd(
+                _mm256_cmpgt_epi64(self.0, other.0))) as usize
+        }
+        #[inline(always)]
+        unsafe fn compress(self, mask: usize) -> Self {
+            let index = _mm_loadl_epi64(COMPRESS_4X64[mask].as_ptr()
+                as *const __m128i);
+            self.permute(_mm256_cvtepu8_epi32(index))
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Sse41I32(__m128i);
+
+    impl Vector for Sse41I32 {
+        type Elem = i32;
+        type Index = __m128i;
+        const LANES: usize = 4;
+        const MAX: i32 = i32::MAX;
+
+        #[inline(always)]
+        unsafe fn splat(item: i32) -> Self {
+            Self(_mm_set1_epi32(item))
+        }
+        #[inline(always)]
+        unsafe fn load(ptr: *const i32) -> Self {
+            Self(_mm_loadu_si128(ptr as *const __m128i))
+        }
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut i32) {
+            _mm_storeu_si128(ptr as *mut __m128i, self.0);
+        }
+        #[inline(always)]
+        unsafe fn min(self, other: Self) -> Self {
+            Self(_mm_min_epi32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn max(self, other: Self) -> Self {
+            Self(_mm_max_epi32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn xor_index(m: usize) -> __m128i {
+            // Lanes are moved as groups of 4 bytes
+            _mm_xor_si128(_mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11,
+                12, 13, 14, 15), _mm_set1_epi8(4 * m as i8))
+        }
+        #[inline(always)]
+        unsafe fn upper_index(m: usize) -> __m128i {
+            let bit = _mm_set1_epi32(1 << m.ilog2());
+            let lanes = _mm_setr_epi32(0, 1, 2, 3);
+            _mm_cmpeq_epi32(_mm_and_si128(lanes, bit), bit)
+        }
+        #[inline(always)]
+        unsafe fn permute(self, index: __m128i) -> Self {
+            Self(_mm_shuffle_epi8(self.0, index))
+        }
+        #[inline(always)]
+        unsafe fn blend(self, other: Self, mask: __m128i) -> Self {
+            Self(_mm_blendv_epi8(self.0, other.0, mask))
+        }
+        #[inline(always)]
+        unsafe fn greater(self, other: Self) -> usize {
+            _mm_movemask_ps(_mm_castsi128_ps(_mm_cmpgt_epi32(self.0, other.0)))
+                as usize
+        }
+        #[inline(always)]
+        unsafe fn compress(self, mask: usize) -> Self {
+            self.permute(_mm_loadu_si128(COMPRESS_4X32[mask].as_ptr()
+                as *const __m128i))
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Sse42I64(__m128i);
+
+    impl Vector for Sse42I64 {
+        type Elem = i64;
+        type Index = __m128i;
+        const LANES: usize = 2;
+        const MAX: i64 = i64::MAX;
+
+        #[inline(always)]
+        unsafe fn splat(item: i64) -> Self {
+            Self(_mm_set1_epi64x(item))
+        }
+        #[inline(always)]
+        unsafe fn load(ptr: *const i64) -> Self {
+            Self(_mm_loadu_si128(ptr as *const __m128i))
+        }
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut i64) {
+            _mm_storeu_si128(ptr as *mut __m128i, self.0);
+        }
+        #[inline(always)]
+        unsafe fn min(self, other: Self) -> Self {
+            let greater = _mm_cmpgt_epi64(self.0, other.0);
+            Self(_mm_blendv_epi8(self.0, other.0, greater))
+        }
+        #[inline(always)]
+        unsafe fn max(self, other: Self) -> Self {
+            let greater = _mm_cmpgt_epi64(self.0, other.0);
+            Self(_mm_blendv_epi8(other.0, self.0, greater))
+        }
+        #[inline(always)]
+        unsafe fn xor_index(m: usize) -> __m128i {
+            // Lanes are moved as groups of 8 bytes
+            _mm_xor_si128(_mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11,
+                12, 13, 14, 15), _mm_set1_epi8(8 * m as i8))
+        }
+        #[inline(always)]
+        unsafe fn upper_index(m: usize) -> __m128i {
+            let bit = _mm_set1_epi64x(1 << m.ilog2());
+            let lanes = _mm_set_epi64x(1, 0);
+            _mm_cmpeq_epi64(_mm_and_si128(lanes, bit), bit)
+        }
+        #[inline(always)]
+        unsafe fn permute(self, index: __m128i) -> Self {
+            Self(_mm_shuffle_epi8(self.0, index))
+        }
+        #[inline(always)]
+        unsafe fn blend(self, other: Self, mask: __m128i) -> Self {
+            Self(_mm_blendv_epi8(self.0, other.0, mask))
+        }
+        #[inline(always)]
+        unsafe fn greater(self, other: Self) -> usize {
+            _mm_movemask_pd(_mm_castsi128_pd(_mm_cmpgt_epi64(self.0, other.0)))
+                as usize
+        }
+        #[inline(always)]
+        unsafe fn compress(self, mask: usize) -> Self {
+            self.permute(_mm_loadu_si128(COMPRESS_2X64[mask].as_ptr()
+                as *const __m128i))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::sealed::Sealed;
+
+    #[cfg(target_arch = "x86_64")]
+    fn isas() -> Vec<Isa> {
+        let mut isas = vec![Isa::Portable];
+        if is_x86_feature_detected!("sse4.1") {isas.push(Isa::Sse41);}
+        if is_x86_feature_detected!("sse4.2") {isas.push(Isa::Sse42);}
+        if is_x86_feature_detected!("avx2") {isas.push(Isa::Avx2);}
+        isas
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn isas() -> Vec<Isa> {
+        vec![Isa::Portable]
+    }
+
+    fn inputs() -> Vec<Vec<i64>> {
+        let mut inputs: Vec<Vec<i64>> = [0, 1, 2, 3, 7, 8, 9, 16, 17, 31, 33,
+            63, 64, 65, 100, 129, 1000, 10_000].iter()
+            .map(|&n| (0..n).map(|x| x * 7919 % 1009 - 500).collect())
+            .collect();
+        inputs.push((0..5000).collect());
+        inputs.push((0..5000).rev().collect());
+        inputs.push(vec![7; 5000]);
+        inputs.push((0..5000).map(|x| x % 3).collect());
+        inputs.push((0..5000).map(|x| if x % 2 == 0 {i64::MIN} else {i64::MAX})
+            .collect());
+        inputs.push((0..100_000).map(|x| (x as u64)
+            .wrapping_mul(0x9e37_79b9_7f4a_7c15) as i64).collect());
+        inputs
+    }
+
+    fn check<T: SimdElement + std::fmt::Debug, F: Fn(&T, &T) -> bool>(
+    input: &[T], equal: F) {
+        let mut expected = input.to_vec();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for isa in isas() {
+            let mut vector_in = input.to_vec();
+            T::sort_with(&mut vector_in, isa);
+            debug_assert!(vector_in.iter().zip(&expected)
+                .all(|(a, b)| equal(a, b)), "{:?}", isa);
+        }
+    }
+
+    #[test]
+    fn test_simd() {
+        let mut vector_in = vec![10, 20, 11, 24, 15];
+        simd_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 15, 20, 24]);
+    }
+    #[test]
+    fn test_simd_32() {
+        for input in inputs() {
+            let input: Vec<i32> = input.iter().map(|&x| x as i32).collect();
+            check(&input, |a, b| a == b);
+            let unsigned: Vec<u32> = input.iter().map(|&x| x as u32).collect();
+            check(&unsigned, |a, b| a == b);
+            let floats: Vec<f32> = input.iter().map(|&x| x as f32 / 3.0)
+                .collect();
+            check(&floats, |a, b| a.to_bits() == b.to_bits());
+        }
+    }
+    #[test]
+    fn test_simd_64() {
+        for input in inputs() {
+            check(&input, |a, b| a == b);
+            let unsigned: Vec<u64> = input.iter().map(|&x| x as u64).collect();
+            check(&unsigned, |a, b| a == b);
+            let floats: Vec<f64> = input.iter().map(|&x| x as f64 / 3.0)
+                .collect();
+            check(&floats, |a, b| a.to_bits() == b.to_bits());
+        }
+    }
+    #[test]
+    fn test_simd_floats() {
+        let input = vec![f64::NAN, 1.0, -0.0, f64::NEG_INFINITY, 0.0, -f64::NAN,
+            f64::INFINITY, f64::MIN_POSITIVE, -1.0];
+        let mut expected = input.clone();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        for isa in isas() {
+            let mut vector_in = input.clone();
+            f64::sort_with(&mut vector_in, isa);
+            let bits = |v: &[f64]| v.iter().map(|x| x.to_bits())
+                .collect::<Vec<_>>();
+            debug_assert_eq!(bits(&vector_in), bits(&expected));
+            let mut vector_in: Vec<f32> = input.iter().map(|&x| x as f32)
+                .collect();
+            f32::sort_with(&mut vector_in, isa);
+            debug_assert!(vector_in.windows(2).all(|w| w[0].total_cmp(&w[1])
+                .is_le()));
+        }
+    }
+    #[test]
+    fn test_simd_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        simd_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_simd_len1() {
+        let mut vector_in = vec![1];
+        simd_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+}
@@ -17,21 +17,23 @@
 /// sorting_rs::slow_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn slow_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
     slow_sort_sorting(input, 0, input.len() - 1);
 }
 
-fn slow_sort_sorting<T: PartialOrd>(input: &mut [T], start: usize, end: usize) {
-    if start >= end {return;}
-
-    let mid = (start + end) / 2;
-    slow_sort_sorting(input, start, mid);
-    slow_sort_sorting(input, mid + 1, end);
-    if input[end] < input[mid] {input.swap(end, mid);}
-    slow_sort_sorting(input, start, end - 1);
+fn slow_sort_sorting<T: PartialOrd>(input: &mut [T], start: usize,
+mut end: usize) {
+    // The last call of the algorithm sorts all but the maximum, it's done in
+    // the loop, so only halves are sorted recursively
+    while start < end {
+        let mid = (start + end) / 2;
+        slow_sort_sorting(input, start, mid);
+        slow_sort_sorting(input, mid + 1, end);
+        if input[end] < input[mid] {input.swap(end, mid);}
+        end -= 1;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,257 @@
+use crate::network_sort::{apply_network, NETWORK_LEN};
+use std::mem::MaybeUninit;
+
+/// Sorts a slice in-place using
+/// [Insertion sort](https://en.wikipedia.org/wiki/Insertion_sort) without
+/// branches on comparison results.
+/// All kinds of slices can be sorted as long as they implement
+/// [`PartialOrd`](https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html).
+///
+/// Classic insertion sort stops scanning sorted elements at the first one not
+/// greater than the inserted element, and that branch is mispredicted once
+/// for every element of random input. Here sorted elements greater than the
+/// inserted one are counted over all of them without stopping, so the loop
+/// has no data-dependent branches and is vectorised for primitive types.
+/// Then the element is moved before them with one rotation. Equal elements
+/// keep their order.
+///
+/// This takes `n`<sup>`2`</sup>`/2` comparisons on any input, so it's meant
+/// for slices of a few dozen elements. Together with sorting networks and a
+/// bidirectional merge it's one of the branchless kernels, which make the
+/// base case of quicksorts, `ksort`, `intro_sort` and `sample_sort`: slices
+/// of up to 16 elements are sorted by networks, and up to 32 elements by
+/// networks on both halves, which are merged from both ends at once, like
+/// the parity merge of [quadsort](https://github.com/scandum/quadsort).
+/// Every step of the merge writes the smaller of the heads to the front and
+/// the larger of the tails to the back, and the halves of equal length can't
+/// run out before the output is full, so there are no branches and no bounds
+/// to check. Networks can swap equal elements, so the base case of
+/// `merge_sort` and `merge_bottom_up_sort` sorts up to 16 elements by this
+/// insertion sort instead, and merges halves the same way, which keeps them
+/// stable.
+///
+/// # Examples
+/// ```rust
+/// let mut vec = vec![-4, -5, 7, 45, 0];
+/// sorting_rs::branchless_insertion_sort(&mut vec);
+/// assert_eq!(vec, &[-5, -4, 0, 7, 45]);
+/// ```
+/// ```rust
+/// let mut strings = vec!["rustc", "cargo", "rustup"];
+/// sorting_rs::branchless_insertion_sort(&mut strings);
+/// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
+/// ```
+pub fn branchless_insertion_sort<T: PartialOrd>(input: &mut [T]) {
+    for i in 1..input.len() {
+        let (sorted, rest) = input.split_at(i);
+        let item = &rest[0];
+        let greater: usize = sorted.iter().map(|x| (item < x) as usize).sum();
+        let position = i - greater;
+        input[position..=i].rotate_right(1);
+    }
+}
+
+/// Largest slice sorted by `small_sort`.
+pub(crate) const SMALL_SORT_LEN: usize = 2 * NETWORK_LEN;
+
+/// Sorts a slice of at most `SMALL_SORT_LEN` elements with networks and
+/// parity merge, used as the base case of other sorts. It's not inlined, so
+/// its stack frame with the unrolled networks and the merge buffer doesn't
+/// enlarge frames of recursive callers.
+#[inline(never)]
+pub(crate) fn small_sort<T: PartialOrd>(input: &mut [T]) {
+    debug_assert!(input.len() <= SMALL_SORT_LEN);
+    if input.len() <= NETWORK_LEN {
+        apply_network(input);
+        return;
+    }
+
+    let mid = input.len() / 2;
+    let (left, right) = input.split_at_mut(mid);
+    for half in [left, right] {
+        apply_network(half);
+    }
+    parity_merge(input, mid);
+}
+
+/// Sorts a slice of at most `SMALL_SORT_LEN` elements like `small_sort`, but
+/// with branchless insertion sort instead of networks, so equal elements keep
+/// their order.
+#[inline(never)]
+pub(crate) fn stable_small_sort<T: PartialOrd>(input: &mut [T]) {
+    debug_assert!(input.len() <= SMALL_SORT_LEN);
+    if input.len() <= NETWORK_LEN {
+        branchless_insertion_sort(input);
+        return;
+    }
+
+    let mid = input.len() / 2;
+    let (left, right) = input.split_at_mut(mid);
+    for half in [left, right] {
+        branchless_insertion_sort(half);
+    }
+    parity_merge(input, mid);
+}
+
+/// Merges sorted halves `input[..mid]` and `input[mid..]`, where `mid` is half
+/// of the length rounded down, from both ends. If comparisons are
+/// inconsistent, the slice may be left unmerged, but it keeps all elements.
+fn parity_merge<T: PartialOrd>(input: &mut [T], mid: usize) {
+    let len = input.len();
+    debug_assert!(mid == len / 2 && len <= SMALL_SORT_LEN);
+    let mut buffer = [const {MaybeUninit::<T>::uninit()}; SMALL_SORT_LEN];
+    let (mut left, mut right) = (0, mid);
+    // Tails may go one past the start of their halves, which wraps around
+    let (mut left_tail, mut right_tail) = (mid.wrapping_sub(1), len - 1);
+
+    // Both fronts and both tails have at least `len / 2` elements, so all
+    // indices are in bounds whatever the comparisons return. Ties are taken
+    // from the left at the front and from the right at the back, so the merge
+    // is stable
+    let mut take = |to: usize, from: usize| {
+        // SAFETY: elements are only copied to the buffer, the slice isn't
+        // changed until all of them are copied exactly once
+        unsafe {buffer[to].as_mut_ptr().copy_from_nonoverlapping(&input[from],
+            1);}
+    };
+    for k in 0..len / 2 {
+        let right_first = input[right] < input[left];
+        take(k, if right_first {right} else {left});
+        right += right_first as usize;
+        left += !right_first as usize;
+
+        let left_last = input[right_tail] < input[left_tail];
+        take(len - 1 - k, if left_last {left_tail} else {right_tail});
+        left_tail = left_tail.wrapping_sub(left_last as usize);
+        right_tail -= !left_last as usize;
+    }
+    if len % 2 == 1 {
+        let left_rest = left_tail.wrapping_add(1) > left;
+        take(len / 2, if left_rest {left} else {right});
+        left += left_rest as usize;
+        right += !left_rest as usize;
+    }
+
+    if left == left_tail.wrapping_add(1) && right == right_tail + 1 {
+        // SAFETY: every element was copied to the buffer once, so the buffer
+        // owns them and moving them back keeps every value owned once
+        unsafe {
+            input.as_mut_ptr().copy_from_nonoverlapping(
+                buffer.as_ptr() as *const T, len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branchless_insertion() {
+        let mut vector_in = vec![10, 20, 11, 24];
+        branchless_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![10, 11, 20, 24]);
+    }
+    #[test]
+    fn test_branchless_insertion_stable() {
+        let mut keys: Vec<Key> = (0..100).map(|i| Key(i * 37 % 7, i))
+            .collect();
+        let mut expected = fields(&keys);
+        expected.sort_by_key(|item| item.0);
+        branchless_insertion_sort(&mut keys);
+        debug_assert_eq!(fields(&keys), expected);
+    }
+    #[test]
+    fn test_small_sort() {
+        for len in 0..=SMALL_SORT_LEN {
+            for seed in 0..20 {
+                let input: Vec<i32> = (0..len)
+                    .map(|i| ((i * 7919 + seed * 104_729) % 13) as i32)
+                    .collect();
+                let mut expected = input.clone();
+                expected.sort();
+                let mut vector_in = input;
+                small_sort(&mut vector_in);
+                debug_assert_eq!(vector_in, expected);
+            }
+        }
+    }
+    #[test]
+    fn test_small_sort_stable_merge() {
+        for len in NETWORK_LEN + 1..=SMALL_SORT_LEN {
+            let mid = len / 2;
+            let mut keys: Vec<Key> = (0..len).map(|i| Key(i * 7 % 3, i))
+                .collect();
+            keys[..mid].sort_by_key(|key| key.0);
+            keys[mid..].sort_by_key(|key| key.0);
+            let mut expected = fields(&keys);
+            expected.sort_by_key(|item| item.0);
+            parity_merge(&mut keys, mid);
+            debug_assert_eq!(fields(&keys), expected);
+        }
+    }
+    #[test]
+    fn test_stable_small_sort() {
+        for len in 0..=SMALL_SORT_LEN {
+            let mut keys: Vec<Key> = (0..len).map(|i| Key(i * 7 % 3, i))
+                .collect();
+            let mut expected = fields(&keys);
+            expected.sort_by_key(|item| item.0);
+            stable_small_sort(&mut keys);
+            debug_assert_eq!(fields(&keys), expected);
+        }
+    }
+    #[test]
+    fn test_small_sort_strings() {
+        let mut vector_in: Vec<String> = (0..30).map(|i| (i * 7 % 30)
+            .to_string()).collect();
+        let mut expected = vector_in.clone();
+        expected.sort();
+        small_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, expected);
+    }
+    #[test]
+    fn test_small_sort_inconsistent() {
+        // NaNs make comparisons inconsistent, the elements must be kept
+        let input: Vec<f64> = (0..31).map(|i| if i % 4 == 0 {f64::NAN}
+            else {(i * 7 % 31) as f64}).collect();
+        let mut vector_in = input.clone();
+        small_sort(&mut vector_in);
+        let bits = |v: &[f64]| {
+            let mut bits: Vec<u64> = v.iter().map(|x| x.to_bits()).collect();
+            bits.sort();
+            bits
+        };
+        debug_assert_eq!(bits(&vector_in), bits(&input));
+    }
+    #[test]
+    fn test_branchless_insertion_empty() {
+        let mut vector_in:Vec<i32> = vec![];
+        branchless_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, &[]);
+    }
+    #[test]
+    fn test_branchless_insertion_len1() {
+        let mut vector_in = vec![1];
+        branchless_insertion_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, vec![1]);
+    }
+
+    /// Compares only the first field, the second one tracks stability.
+    #[derive(Debug)]
+    struct Key(usize, usize);
+
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {self.0 == other.0}
+    }
+
+    impl PartialOrd for Key {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    fn fields(keys: &[Key]) -> Vec<(usize, usize)> {
+        keys.iter().map(|key| (key.0, key.1)).collect()
+    }
+}
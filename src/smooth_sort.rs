@@ -18,8 +18,13 @@
 /// ```
 /// Where *+ 1* is "add" number and "Fib" are Fibonacci numbers.
 /// 
-/// For 64-bit systems it's possible to use 90 Leonardo numbers placed as a
-/// constant array [usize; 90].
+/// Leonardo numbers fitting into `usize` are calculated at compile time and
+/// placed into a constant array, there are 92 of them on 64-bit systems and
+/// 46 on 32-bit ones.
+///
+/// Orders of the heap trees are stored as a bit-vector, as proposed by
+/// Dijkstra, so the sort doesn't allocate and needs only constant additional
+/// memory.
 ///
 /// # Examples
 /// ```rust
@@ -32,60 +37,187 @@
 /// sorting_rs::smooth_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn smooth_sort<T: PartialOrd>(input: &mut [T])
 {
     if input.len() < 2 {return;}
     
-    // Init addtitional index heap
-    let input = input;
+    // Shape of the heap is kept in a bit-vector, so no allocation is needed
     let in_len = input.len();
-    let mut heap = Vec::<usize>::new();
+    let mut heap = Shape::default();
 
     for i in 0..in_len {
-        if heap.len() >= 2 && heap[heap.len() - 2] == heap[heap.len() - 1] + 1 {
-            heap.pop();
-            let len_leo = heap.len();
-            heap[len_leo - 1] += 1;
-        } else if heap.len() >= 1 && heap[heap.len() - 1] == 1 {
-            heap.push(0);
-        } else {
-            heap.push(1);
-        }
-        restore_heap(input, i, &heap);
+        leonardo_push(input, i, &mut heap);
     }
 
     for i in (0..in_len).rev() {
-        if heap[heap.len() - 1] < 2 {
-            heap.pop();
-        } else {
-            let k = heap.pop().unwrap();
-            let t = get_child_trees(i, k);
-            // tr kr tl kl
-            // 0  1  2  3
-            heap.push(t[3]);
-            restore_heap(input, t[2], &heap);
-            heap.push(t[1]);
-            restore_heap(input, t[0], &heap);
+        leonardo_pop(input, i, &mut heap);
+    }
+}
+
+/// Max-priority queue based on Leonardo heap of smoothsort.
+///
+/// Leonardo heap is a list of heaps of decreasing Leonardo number sizes, with
+/// their roots sorted in ascending order. The largest element is always the
+/// last one, so it's removed without moving other elements. Insertion and
+/// removal take `logn` time, but only constant time if elements come in
+/// sorted order.
+///
+/// # Examples
+/// ```rust
+/// use sorting_rs::LeonardoHeap;
+///
+/// let mut heap = LeonardoHeap::from_vec(vec![5, 2, 7]);
+/// heap.push(9);
+/// assert_eq!(heap.peek(), Some(&9));
+/// assert_eq!(heap.pop(), Some(9));
+/// assert_eq!(heap.into_sorted_vec(), &[2, 5, 7]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LeonardoHeap<T> {
+    data: Vec<T>,
+    heap: Shape,
+}
+
+impl<T: PartialOrd> LeonardoHeap<T> {
+    pub fn new() -> Self {
+        Self { data: Vec::new(), heap: Shape::default() }
+    }
+
+    /// Builds heap from a vector in-place.
+    pub fn from_vec(mut data: Vec<T>) -> Self {
+        let mut heap = Shape::default();
+        for i in 0..data.len() {
+            leonardo_push(&mut data, i, &mut heap);
+        }
+        Self { data, heap }
+    }
+
+    pub fn push(&mut self, item: T) {
+        let i = self.data.len();
+        self.data.push(item);
+        leonardo_push(&mut self.data, i, &mut self.heap);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {return None;}
+
+        let i = self.data.len() - 1;
+        leonardo_pop(&mut self.data, i, &mut self.heap);
+        self.data.pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns elements in heap order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns elements sorted in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for i in (0..self.data.len()).rev() {
+            leonardo_pop(&mut self.data, i, &mut self.heap);
         }
+        self.data
     }
 }
 
-fn restore_heap<T: PartialOrd>(input: &mut [T], index: usize, heap: &Vec<usize>)
-{
+impl<T: PartialOrd> Default for LeonardoHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shape of Leonardo heap in
+/// [Dijkstra's](https://www.cs.utexas.edu/users/EWD/ewd07xx/EWD796a.PDF)
+/// bit-vector form: bit `j` of `trees` is set if the heap has a tree of order
+/// `shift + j`. Orders of trees strictly decrease, so the bits describe the
+/// whole heap and the last tree has order `shift`. Leonardo numbers above
+/// `usize::MAX` are not needed, so 128 bits are always enough.
+#[derive(Debug, Clone, Copy, Default)]
+struct Shape {
+    trees: u128,
+    shift: usize,
+}
+
+impl Shape {
+    /// Moves to the tree before the last one, returns `false` if there is
+    /// no such tree.
+    fn prev(&mut self) -> bool {
+        self.trees >>= 1;
+        if self.trees == 0 {return false;}
+        let zeros = self.trees.trailing_zeros();
+        self.trees >>= zeros;
+        self.shift += 1 + zeros as usize;
+        true
+    }
+}
+
+/// Adds element at index `i` to the heap formed by previous elements.
+fn leonardo_push<T: PartialOrd>(input: &mut [T], i: usize, heap: &mut Shape) {
+    if heap.trees == 0 {
+        heap.trees = 1;
+        heap.shift = 1;
+    } else if heap.trees & 3 == 3 {
+        // Two last trees of consecutive orders are merged with the new root
+        heap.trees = (heap.trees >> 2) | 1;
+        heap.shift += 2;
+    } else if heap.shift == 1 {
+        heap.trees = (heap.trees << 1) | 1;
+        heap.shift = 0;
+    } else {
+        heap.trees = (heap.trees << (heap.shift - 1)) | 1;
+        heap.shift = 1;
+    }
+    restore_heap(input, i, *heap);
+}
+
+/// Removes the largest element, which is the last one at index `i`, from the
+/// heap.
+fn leonardo_pop<T: PartialOrd>(input: &mut [T], i: usize, heap: &mut Shape) {
+    if heap.shift < 2 {
+        heap.prev();
+    } else {
+        let t = get_child_trees(i, heap.shift);
+        // tr kr tl kl
+        // 0  1  2  3
+        heap.trees = ((heap.trees & !1) << 2) | 3;
+        heap.shift -= 2;
+        let mut left = *heap;
+        left.prev();
+        restore_heap(input, t[2], left);
+        restore_heap(input, t[0], *heap);
+    }
+}
+
+fn restore_heap<T: PartialOrd>(input: &mut [T], index: usize,
+mut heap: Shape) {
     // Insertion sorting
-    let mut current = heap.len() - 1;
     let mut i = index;
-    let mut k = heap[current];
+    let mut k = heap.shift;
 
-    while current > 0 {
+    while i >= crate::LEO_NUMS[k] {
         let j = i - crate::LEO_NUMS[k];
-        if input[j] > input[i] &&
-        (k < 2 || input[j] > input[i - 1] && input[j] > input[i - 2]) {
+        // Root of the previous tree is compared with the root and children of
+        // the current tree, as the root may be not sifted down yet
+        if input[j] > input[i] && (k < 2 || {
+            let t = get_child_trees(i, k);
+            input[j] > input[t[0]] && input[j] > input[t[2]]
+        }) {
             input.swap(i, j);
             i = j;
-            current -= 1;
-            k = heap[current];
+            heap.prev();
+            k = heap.shift;
         } else {
             break;
         }
@@ -136,6 +268,15 @@ mod tests {
         debug_assert_eq!(vector_in, &[1, 2, 8, 9, 10, 11, 13, 20, 24]);
     }
     #[test]
+    fn test_smooth_02() {
+        let mut vector_in: Vec<i32> = (0..300).map(|x| (x * 7919 + 13) % 101)
+            .collect();
+        let mut reference = vector_in.clone();
+        reference.sort();
+        smooth_sort(&mut vector_in);
+        debug_assert_eq!(vector_in, reference);
+    }
+    #[test]
     fn test_smooth_empty() {
         let mut vector_in:Vec<i32> = vec![];
         smooth_sort(&mut vector_in);
@@ -147,4 +288,37 @@ mod tests {
         smooth_sort(&mut vector_in);
         debug_assert_eq!(vector_in, &[1]);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_leonardo_heap_queue() {
+        let mut heap = LeonardoHeap::new();
+        let mut reference = vec![];
+        for i in 0..500 {
+            let item = (i * 367) % 113;
+            heap.push(item);
+            reference.push(item);
+            if i % 3 == 0 {
+                reference.sort();
+                debug_assert_eq!(heap.peek(), reference.last());
+                debug_assert_eq!(heap.pop(), reference.pop());
+            }
+        }
+        debug_assert_eq!(heap.len(), reference.len());
+        reference.sort();
+        debug_assert_eq!(heap.clone().into_sorted_vec(), reference);
+        while let Some(item) = heap.pop() {
+            debug_assert_eq!(Some(item), reference.pop());
+        }
+        debug_assert!(heap.is_empty());
+    }
+    #[test]
+    fn test_leonardo_heap_queue_from_vec() {
+        let input: Vec<i32> = (0..100).map(|x| (x * 37) % 101).collect();
+        let mut reference = input.clone();
+        reference.sort();
+        let heap = LeonardoHeap::from_vec(input.clone());
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+        let mut heap = LeonardoHeap::from_vec(input);
+        debug_assert_eq!(heap.pop(), reference.pop());
+        debug_assert_eq!(heap.into_sorted_vec(), reference);
+    }
+}
@@ -17,7 +17,6 @@
 /// sorting_rs::stooge_sort(&mut strings);
 /// assert_eq!(strings, &["cargo", "rustc", "rustup"]);
 /// ```
-
 pub fn stooge_sort<T: PartialOrd>(input: &mut [T]) {
     if input.len() < 2 {return;}
 
@@ -27,13 +26,11 @@ pub fn stooge_sort<T: PartialOrd>(input: &mut [T]) {
 fn stooge_sort_sorting<T: PartialOrd>(input: &mut [T], start: usize,
 end: usize) {
     if input[start] > input[end] {input.swap(start, end);}
-    if start <= end {
-        if (end - start + 1) > 2 {
-            let temp = (end - start + 1) / 3;
-            stooge_sort_sorting(input, start, end - temp);
-            stooge_sort_sorting(input, start + temp, end);
-            stooge_sort_sorting(input, start, end - temp);
-        }
+    if start <= end && (end - start + 1) > 2 {
+        let temp = (end - start + 1) / 3;
+        stooge_sort_sorting(input, start, end - temp);
+        stooge_sort_sorting(input, start + temp, end);
+        stooge_sort_sorting(input, start, end - temp);
     }
 }
 
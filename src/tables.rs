@@ -0,0 +1,185 @@
+//! Constant tables used by the sorting algorithms.
+//!
+//! Tables are generated for the target pointer width, so they contain only
+//! values fitting into `usize`. Sources of the tables can be printed and the
+//! tables checked by the `gen_tables` binary, which includes this file too.
+
+/// Powers of 2 from 2 to the largest one fitting into `usize`
+pub(crate) const POWERS_OF_TWO: [usize; usize::BITS as usize - 1] =
+    powers_of_two();
+
+/// Leonardo numbers up to the largest one fitting into `usize`
+pub(crate) const LEO_NUMS: [usize; leonardo_len(usize::MAX as u128)] =
+    leonardo_numbers();
+
+/// Ciura gaps, extended by multiplying by 2.25
+pub(crate) const CIURA_GAPS: [usize; fitting_len(&CIURA_GAPS_64,
+    usize::MAX as u128)] = fit_table(&CIURA_GAPS_64);
+
+/// Tokuda gaps
+pub(crate) const TOKUDA_GAPS: [usize; fitting_len(&TOKUDA_GAPS_64,
+    usize::MAX as u128)] = fit_table(&TOKUDA_GAPS_64);
+
+/// Pratt gaps, 3-smooth numbers up to the largest one fitting into `usize`
+pub(crate) const PRATT_GAPS: [usize; pratt_len(usize::MAX as u128)] =
+    pratt_numbers();
+
+pub(crate) const CIURA_GAPS_64: [u64; 54] = [
+    1, 4, 10, 23, 57, 132, 301, 701, 1750, 3937, 8858, 19930, 44842, 100894,
+    227011, 510774, 1149241, 2585792, 5818032, 13090572, 29453787, 66271020,
+    149109795, 335497038, 754868335, 1698453753, 3821520944, 8598422124,
+    19346449779, 43529512002, 97941402004, 220368154509, 495828347645,
+    1115613782201, 2510131009952, 5647794772392, 12707538237882, 28591961035234,
+    64331912329276, 144746802740871, 325680306166959, 732780688875657,
+    1648756549970228, 3709702237433013, 8346830034224279, 18780367577004627,
+    42255827048260410, 95075610858585922, 213920124431818324,
+    481320279971591229, 1082970629936080265, 2436683917356180596,
+    5482538814051406341, 12335712331615664267,
+];
+
+pub(crate) const TOKUDA_GAPS_64: [u64; 54] = [
+    1, 4, 9, 20, 46, 103, 233, 525, 1182, 2660, 5985, 13467, 30301, 68178,
+    153401, 345152, 776591, 1747331, 3931496, 8845866, 19903198, 44782196,
+    100759940, 226709866, 510097200, 1147718700, 2582367076, 5810325920,
+    13073233321, 29414774973, 66183243690, 148912298303, 335052671183,
+    753868510162, 1696204147864, 3816459332694, 8587033498562, 19320825371765,
+    43471857086472, 97811678444563, 220076276500268, 495171622125603,
+    1114136149782608, 2506806337010869, 5640314258274455, 12690707081117525,
+    28554090932514431, 64246704598157469, 144555085345854306,
+    325248942028172190, 731810119563387427, 1646572769017621711,
+    3704788730289648850, 8335774643151709914,
+];
+
+/// Returns `[2, 4, ..., 2^N]`.
+const fn powers_of_two<const N: usize>() -> [usize; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    while i < N {
+        table[i] = 2 << i;
+        i += 1;
+    }
+    table
+}
+
+/// Returns number of Leonardo numbers not greater than `max`.
+const fn leonardo_len(max: u128) -> usize {
+    let (mut a, mut b) = (1u128, 1u128);
+    let mut len = 0;
+    while a <= max {
+        let next = a + b + 1;
+        a = b;
+        b = next;
+        len += 1;
+    }
+    len
+}
+
+const fn leonardo_numbers<const N: usize>() -> [usize; N] {
+    let mut table = [1; N];
+    let mut i = 2;
+    while i < N {
+        table[i] = table[i - 1] + table[i - 2] + 1;
+        i += 1;
+    }
+    table
+}
+
+/// Returns number of 3-smooth numbers not greater than `max`.
+const fn pratt_len(max: u128) -> usize {
+    let mut len = 0;
+    let mut power2 = 1u128;
+    while power2 <= max {
+        let mut n = power2;
+        while n <= max {
+            len += 1;
+            n *= 3;
+        }
+        power2 *= 2;
+    }
+    len
+}
+
+/// Returns first `N` 3-smooth numbers. Every number is the smallest of
+/// doubled and tripled smaller ones, which are tracked by two indexes.
+const fn pratt_numbers<const N: usize>() -> [usize; N] {
+    let mut table = [1; N];
+    let (mut i2, mut i3) = (0, 0);
+    let mut i = 1;
+    while i < N {
+        let by2 = table[i2] as u128 * 2;
+        let by3 = table[i3] as u128 * 3;
+        let next = if by2 < by3 {by2} else {by3};
+        if by2 == next {i2 += 1;}
+        if by3 == next {i3 += 1;}
+        table[i] = next as usize;
+        i += 1;
+    }
+    table
+}
+
+/// Returns number of leading entries of increasing `table` not greater than
+/// `max`.
+const fn fitting_len(table: &[u64], max: u128) -> usize {
+    let mut len = 0;
+    while len < table.len() && table[len] as u128 <= max {
+        len += 1;
+    }
+    len
+}
+
+/// Returns first `N` entries of `table` as `usize`.
+const fn fit_table<const N: usize>(table: &[u64]) -> [usize; N] {
+    let mut fitted = [0; N];
+    let mut i = 0;
+    while i < N {
+        fitted[i] = table[i] as usize;
+        i += 1;
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powers_of_two() {
+        debug_assert_eq!(POWERS_OF_TWO[0], 2);
+        debug_assert_eq!(POWERS_OF_TWO[POWERS_OF_TWO.len() - 1],
+            1 << (usize::BITS - 1));
+        debug_assert!(POWERS_OF_TWO.windows(2).all(|w| w[1] == w[0] * 2));
+    }
+    #[test]
+    fn test_leonardo_numbers() {
+        debug_assert_eq!(&LEO_NUMS[..6], &[1, 1, 3, 5, 9, 15]);
+        debug_assert!(LEO_NUMS.windows(3).all(|w| w[2] == w[1] + w[0] + 1));
+        let last = LEO_NUMS.len() - 1;
+        debug_assert!(LEO_NUMS[last].checked_add(LEO_NUMS[last - 1] + 1)
+            .is_none());
+    }
+    #[test]
+    fn test_leonardo_len() {
+        debug_assert_eq!(leonardo_len(u16::MAX as u128), 23);
+        debug_assert_eq!(leonardo_len(u32::MAX as u128), 46);
+        debug_assert_eq!(leonardo_len(u64::MAX as u128), 92);
+    }
+    #[test]
+    fn test_pratt_numbers() {
+        debug_assert_eq!(&PRATT_GAPS[..10], &[1, 2, 3, 4, 6, 8, 9, 12, 16, 18]);
+        debug_assert!(PRATT_GAPS.windows(2).all(|w| w[0] < w[1]));
+        debug_assert_eq!(pratt_len(10), 7);
+        debug_assert_eq!(pratt_len(u16::MAX as u128), 94);
+    }
+    #[test]
+    fn test_gap_tables() {
+        debug_assert_eq!(fitting_len(&CIURA_GAPS_64, u16::MAX as u128), 13);
+        debug_assert_eq!(fitting_len(&CIURA_GAPS_64, u32::MAX as u128), 27);
+        debug_assert_eq!(fitting_len(&CIURA_GAPS_64, u64::MAX as u128), 54);
+        debug_assert_eq!(fitting_len(&TOKUDA_GAPS_64, u16::MAX as u128), 13);
+        debug_assert_eq!(fitting_len(&TOKUDA_GAPS_64, u32::MAX as u128), 27);
+        debug_assert!(CIURA_GAPS.iter().zip(CIURA_GAPS_64.iter())
+            .all(|(a, b)| *a as u64 == *b));
+        debug_assert!(TOKUDA_GAPS.iter().zip(TOKUDA_GAPS_64.iter())
+            .all(|(a, b)| *a as u64 == *b));
+    }
+}
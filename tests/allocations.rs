@@ -0,0 +1,95 @@
+//! Checks memory used by the sorting functions by counting allocations made
+//! by the current thread, so tests running in parallel don't interfere.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATED.try_with(|a| a.set(a.get() + layout.size()));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Returns number of bytes allocated while running `f`.
+fn allocated_by<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATED.with(|a| a.get());
+    f();
+    ALLOCATED.with(|a| a.get()) - before
+}
+
+fn shuffled(n: u64) -> Vec<u64> {
+    (0..n).map(|x| (x * 7919 + 13) % n).collect()
+}
+
+#[test]
+fn test_smooth_no_allocations() {
+    let mut vector_in = shuffled(10_000);
+    assert_eq!(allocated_by(|| sorting_rs::smooth_sort(&mut vector_in)),
+        0);
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_weak_heap_bits() {
+    let mut vector_in = shuffled(10_000);
+    let bytes = allocated_by(|| sorting_rs::weak_heap_sort(&mut vector_in));
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    // One bit per element, rounded up to whole words
+    assert_eq!(bytes, 10_000usize.div_ceil(usize::BITS as usize)
+        * std::mem::size_of::<usize>());
+}
+
+#[test]
+fn test_weak_heap_with_buffer_no_allocations() {
+    let mut vector_in = shuffled(10_000);
+    let mut buffer = [0; sorting_rs::weak_heap_buffer_len(10_000)];
+    assert_eq!(allocated_by(||
+        sorting_rs::weak_heap_sort_with_buffer(&mut vector_in, &mut buffer)),
+        0);
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_merge_one_buffer() {
+    let mut vector_in = shuffled(10_000);
+    let bytes = allocated_by(|| sorting_rs::merge_sort(&mut vector_in));
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(bytes, 10_000 * std::mem::size_of::<u64>());
+
+    let mut vector_in = shuffled(10_000);
+    let bytes = allocated_by(||
+        sorting_rs::merge_bottom_up_sort(&mut vector_in));
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(bytes, 10_000 * std::mem::size_of::<u64>());
+}
+
+#[test]
+fn test_merge_with_buffer_no_allocations() {
+    let mut vector_in = shuffled(10_000);
+    let mut buffer = vec![0; 10_000];
+    assert_eq!(allocated_by(||
+        sorting_rs::merge_sort_with_buffer(&mut vector_in, &mut buffer)), 0);
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_shell_pratt_no_allocations() {
+    let mut vector_in = shuffled(10_000);
+    assert_eq!(allocated_by(|| sorting_rs::shell_sort_with(&mut vector_in,
+        &sorting_rs::shell_sort::Pratt)), 0);
+    assert!(vector_in.windows(2).all(|w| w[0] <= w[1]));
+}
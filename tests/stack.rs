@@ -0,0 +1,62 @@
+//! Runs recursive sorts on inputs, which make them split ranges as unevenly
+//! as possible, in a thread with a small stack, so linear recursion depth
+//! would overflow it.
+
+use std::thread;
+
+/// Stack of the sorting thread. It holds the frame of the unrolled base case,
+/// which is large in debug builds, and logarithmic recursion only.
+const STACK_SIZE: usize = 256 * 1024;
+
+/// Sorts sorted and reversed inputs of length `n` in a thread with a small
+/// stack.
+fn check(n: u32, sort: fn(&mut [u32])) {
+    thread::Builder::new().stack_size(STACK_SIZE).spawn(move || {
+        let sorted: Vec<u32> = (0..n).collect();
+        for input in [sorted.clone(), sorted.iter().rev().copied().collect()] {
+            let mut vector_in = input;
+            sort(&mut vector_in);
+            assert_eq!(vector_in, sorted);
+        }
+    }).unwrap().join().unwrap();
+}
+
+#[test]
+fn test_quick_stack() {
+    check(10_000, sorting_rs::quick_sort);
+    check(10_000, |input| sorting_rs::quick_sort_with(input,
+        sorting_rs::PivotStrategy::First));
+    check(100_000, sorting_rs::quick_hoare_sort);
+    check(100_000, sorting_rs::quick_3way_sort);
+}
+
+#[test]
+fn test_quick_dual_stack() {
+    check(10_000, sorting_rs::quick_dual_sort);
+}
+
+#[test]
+fn test_ksort_stack() {
+    check(10_000, sorting_rs::ksort);
+}
+
+#[test]
+fn test_merge_stack() {
+    check(100_000, sorting_rs::merge_sort);
+    check(100_000, sorting_rs::merge_bottom_up_sort);
+}
+
+#[test]
+fn test_bitonic_stack() {
+    thread::Builder::new().stack_size(STACK_SIZE).spawn(|| {
+        let mut vector_in: Vec<u32> = (0..1 << 14).rev().collect();
+        sorting_rs::bitonic_sort(&mut vector_in);
+        assert_eq!(vector_in, (0..1 << 14).collect::<Vec<u32>>());
+    }).unwrap().join().unwrap();
+}
+
+#[test]
+fn test_slow_stooge_stack() {
+    check(24, sorting_rs::slow_sort);
+    check(300, sorting_rs::stooge_sort);
+}